@@ -1,17 +1,24 @@
 use yew::prelude::*;
 
+use crate::model::CacheStats;
 use crate::views::{DebugInfo, Pool};
 
 #[function_component]
 pub fn App() -> Html {
+    let cache = use_state_eq(|| None::<CacheStats>);
+    let on_cache = {
+        let cache = cache.clone();
+        Callback::from(move |stats| cache.set(Some(stats)))
+    };
+
     html! {
         <main>
             <section class= "section is-medium">
                 <h1 class="title">{ "Rumikub solver" }</h1>
-                <Pool />
+                <Pool {on_cache} />
             </section>
             <section>
-                <DebugInfo />
+                <DebugInfo cache={*cache} />
             </section>
         </main>
     }
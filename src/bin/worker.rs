@@ -0,0 +1,6 @@
+use gloo_worker::Registrable;
+use rummisolve::Simulator;
+
+fn main() {
+    Simulator::registrar().register();
+}
@@ -0,0 +1,7 @@
+mod app;
+mod model;
+mod utils;
+mod views;
+
+pub use app::App;
+pub use views::simulate::Simulator;
@@ -1,9 +1,4 @@
-mod app;
-mod views;
-mod model;
-mod utils;
-
-use app::App;
+use rummisolve::App;
 
 fn main() {
 	wasm_logger::init(wasm_logger::Config::default());
@@ -0,0 +1,173 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use super::{Combos, Tile, TileSet};
+
+const MAX_SPLITS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NearMeld {
+    pub tiles: TileSet,
+    pub missing: Tile,
+}
+
+// The splits into `combos` that lay the most tiles, each using every rack tile
+// it can: the lowest tile left either starts a meld or stays on the rack, and
+// the search keeps the best MAX_SPLITS found so far to cut branches that can't
+// beat them.
+pub fn splits(combos: &Combos, rack: TileSet) -> Vec<Vec<TileSet>> {
+    let mut search = Splits {
+        combos,
+        found: vec![],
+        seen: HashSet::new(),
+    };
+    search.collect(rack, TileSet::default(), &mut vec![]);
+    search.found.sort_by_key(|&(used, _)| Reverse(used));
+    search.found.into_iter().map(|(_, melds)| melds).collect()
+}
+
+#[derive(Debug)]
+struct Splits<'a> {
+    combos: &'a Combos,
+    found: Vec<(usize, Vec<TileSet>)>,
+    seen: HashSet<Vec<TileSet>>,
+}
+
+impl Splits<'_> {
+    fn collect(&mut self, rest: TileSet, left: TileSet, melds: &mut Vec<TileSet>) {
+        let used: usize = melds.iter().map(|meld| meld.len()).sum();
+        if self.found.len() >= MAX_SPLITS && used + rest.len() <= self.worst() {
+            return;
+        }
+        let Some(first) = rest.into_iter().next() else {
+            if !melds.is_empty() {
+                self.keep(used, melds);
+            }
+            return;
+        };
+        for combo in self.combos.with(first) {
+            if let Ok(after) = rest.checked_sub(combo) {
+                melds.push(combo);
+                self.collect(after, left, melds);
+                melds.pop();
+            }
+        }
+        // a tile only stays on the rack if no meld could be made of those left
+        let left = left + first;
+        if !self.combos.with(first).any(|combo| combo <= left) {
+            self.collect(rest - first, left, melds);
+        }
+    }
+
+    fn worst(&self) -> usize {
+        self.found.iter().map(|&(used, _)| used).min().unwrap_or(0)
+    }
+
+    fn keep(&mut self, used: usize, melds: &[TileSet]) {
+        let mut key = melds.to_vec();
+        key.sort_by_key(|&meld| meld.into_iter().collect::<Vec<_>>());
+        if !self.seen.insert(key) {
+            return;
+        }
+        self.found.push((used, melds.to_vec()));
+        if self.found.len() > MAX_SPLITS {
+            self.found.sort_by_key(|&(used, _)| Reverse(used));
+            self.found.truncate(MAX_SPLITS);
+        }
+    }
+}
+
+pub fn near_melds(combos: &Combos, rack: TileSet) -> Vec<NearMeld> {
+    let near: HashSet<_> = combos
+        .all()
+        .filter(|&combo| combo.len() == 3 && combo.jokers() == 0)
+        .flat_map(|combo| {
+            combo
+                .unique_tiles()
+                .filter(move |&missing| rack.amount(missing) < combo.amount(missing))
+                .filter_map(move |missing| {
+                    let tiles = combo.try_remove(missing).ok()?;
+                    Some(NearMeld { tiles, missing })
+                })
+        })
+        .filter(|near| near.tiles <= rack)
+        .collect();
+    let mut near: Vec<_> = near.into_iter().collect();
+    near.sort_by_key(|near| (near.missing, near.tiles.into_iter().collect::<Vec<_>>()));
+    near
+}
+
+pub fn best_draw(combos: &Combos, rack: TileSet) -> Option<(Tile, usize)> {
+    let mut completed = HashMap::new();
+    for near in near_melds(combos, rack) {
+        *completed.entry(near.missing).or_insert(0) += 1;
+    }
+    completed
+        .into_iter()
+        .max_by_key(|&(tile, count)| (count, Reverse(tile)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::solver::LinksSolver;
+    use crate::model::{meld, tile, tiles, PieceUniverse, Rummikub, Solver};
+    use crate::utils::rng::Rng;
+
+    #[test]
+    fn splits_use_the_most_tiles_first() {
+        let rack = meld!(R1..=R6) + tiles![G1, B1];
+        let found = splits(Rummikub.combos(), rack);
+        let used = |melds: &Vec<TileSet>| melds.iter().map(|meld| meld.len()).sum::<usize>();
+        assert_eq!(used(&found[0]), 8);
+        assert!(found
+            .windows(2)
+            .all(|pair| used(&pair[0]) >= used(&pair[1])));
+        assert!(found.contains(&vec![meld!(R1..=R6)]));
+        for melds in &found {
+            let laid = melds
+                .iter()
+                .fold(TileSet::default(), |all, &meld| all + meld);
+            let left = rack.checked_sub(laid).unwrap();
+            assert!(
+                !Rummikub.combos().all().any(|combo| combo <= left),
+                "{melds:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn the_best_split_survives_the_limit() {
+        let deck: Vec<Tile> = crate::model::draw::full_deck().into_iter().collect();
+        let solver = LinksSolver::new(Rummikub);
+        for seed in 0..5 {
+            let rack: TileSet = Rng::seeded(seed).sample(&deck, 18).into_iter().collect();
+            let best = solver
+                .solve(TileSet::default(), rack)
+                .unwrap()
+                .played_count();
+            let found = splits(Rummikub.combos(), rack);
+            let used = found
+                .first()
+                .map_or(0, |melds| melds.iter().map(|meld| meld.len()).sum());
+            assert_eq!(used, best, "{rack:?}");
+            assert!(found.len() <= MAX_SPLITS);
+        }
+    }
+
+    #[test]
+    fn near_melds_and_draws() {
+        let rack = tiles![R4, R6, B5, G5];
+        let near = near_melds(Rummikub.combos(), rack);
+        assert!(near.contains(&NearMeld {
+            tiles: tiles![R4, R6],
+            missing: tile!(R5)
+        }));
+        assert!(near.contains(&NearMeld {
+            tiles: tiles![B5, G5],
+            missing: tile!(U5)
+        }));
+        assert_eq!(best_draw(Rummikub.combos(), rack), Some((tile!(R5), 2)));
+        assert_eq!(best_draw(Rummikub.combos(), tiles![R1, B9]), None);
+    }
+}
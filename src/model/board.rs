@@ -0,0 +1,340 @@
+use std::fmt::Display;
+use std::ops::Add;
+
+use serde::{Deserialize, Serialize};
+
+use super::{is_combo, Color, Tile, TileSet, TileSetError, Value};
+
+const LOWEST: u8 = 1;
+const HIGHEST: u8 = 13;
+const SHORTEST: u8 = 3;
+const COLORS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardError {
+    NotAMeld(TileSet),
+    NoMeld(usize),
+    NotARun(usize),
+    NotAGroup(usize),
+    TooShort(Value),
+    NotAdjacent(usize, usize),
+    ColorTaken(Color),
+    NoJoker(usize),
+    NotStoodFor(Tile),
+    Tiles(TileSetError),
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            BoardError::NotAMeld(tiles) => write!(f, "{tiles:?} is not a run or a group"),
+            BoardError::NoMeld(index) => write!(f, "There is no meld {index}"),
+            BoardError::NotARun(index) => write!(f, "Meld {index} is not a run"),
+            BoardError::NotAGroup(index) => write!(f, "Meld {index} is not a group"),
+            BoardError::TooShort(at) => write!(f, "Splitting before {at} leaves a short run"),
+            BoardError::NotAdjacent(a, b) => write!(f, "Runs {a} and {b} don't meet"),
+            BoardError::ColorTaken(color) => write!(f, "The group already has {}", color.name()),
+            BoardError::NoJoker(index) => write!(f, "Meld {index} has no joker"),
+            BoardError::NotStoodFor(tile) => write!(f, "No joker there stands for {tile:?}"),
+            BoardError::Tiles(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<TileSetError> for BoardError {
+    fn from(error: TileSetError) -> Self {
+        BoardError::Tiles(error)
+    }
+}
+
+// A run knows where it starts, so every joker in it stands for one value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Meld {
+    Run {
+        color: Color,
+        low: Value,
+        tiles: TileSet,
+    },
+    Group {
+        value: Value,
+        tiles: TileSet,
+    },
+}
+
+fn real(tiles: TileSet) -> impl Iterator<Item = (Color, Value)> {
+    tiles.into_iter().filter_map(|tile| match tile {
+        Tile::Normal { color, value } => Some((color, value)),
+        Tile::Joker | Tile::Special(_) => None,
+    })
+}
+
+fn size(tiles: TileSet) -> u8 {
+    tiles.len() as u8
+}
+
+fn value(value: u8) -> Option<Value> {
+    Value::from_code(value as u64)
+}
+
+impl Meld {
+    // Real tiles of one colour make a run, which starts as low as the tiles
+    // allow without running past 13. Anything else has to be a group.
+    pub fn new(tiles: TileSet) -> Result<Self, BoardError> {
+        let reals: Vec<_> = real(tiles).collect();
+        let one_color = reals.windows(2).all(|pair| pair[0].0 == pair[1].0);
+        let meld = match reals[..] {
+            [(color, _), _, ..] if one_color => {
+                let lowest = reals.iter().map(|&(_, value)| value.value()).min();
+                let low = lowest.unwrap_or(LOWEST).min(HIGHEST + 1 - size(tiles));
+                Meld::Run {
+                    color,
+                    low: value(low).ok_or(BoardError::NotAMeld(tiles))?,
+                    tiles,
+                }
+            }
+            [(_, value), ..] => Meld::Group { value, tiles },
+            [] => return Err(BoardError::NotAMeld(tiles)),
+        };
+        meld.checked()
+    }
+
+    pub fn run(color: Color, low: Value, tiles: TileSet) -> Result<Self, BoardError> {
+        Meld::Run { color, low, tiles }.checked()
+    }
+
+    fn checked(self) -> Result<Self, BoardError> {
+        let tiles = self.tiles();
+        let valid = is_combo(tiles)
+            && match self {
+                Meld::Run { color, low, .. } => {
+                    let high = low.value() + size(tiles) - 1;
+                    high <= HIGHEST
+                        && real(tiles).all(|(other, value)| {
+                            other == color && (low.value()..=high).contains(&value.value())
+                        })
+                }
+                Meld::Group { value, .. } => real(tiles).all(|(_, other)| other == value),
+            };
+        valid.then_some(self).ok_or(BoardError::NotAMeld(tiles))
+    }
+
+    pub fn tiles(self) -> TileSet {
+        match self {
+            Meld::Run { tiles, .. } | Meld::Group { tiles, .. } => tiles,
+        }
+    }
+
+    // the tiles its jokers stand for
+    pub fn stood_for(self) -> Vec<Tile> {
+        let tiles = self.tiles();
+        match self {
+            Meld::Run { color, low, .. } => (low.value()..low.value() + size(tiles))
+                .filter_map(value)
+                .map(|value| Tile::Normal { color, value })
+                .filter(|&tile| !tiles.cointains(tile))
+                .collect(),
+            Meld::Group { value, .. } if tiles.cointains(Tile::Joker) => Color::all()
+                .map(|color| Tile::Normal { color, value })
+                .filter(|&tile| !tiles.cointains(tile))
+                .collect(),
+            Meld::Group { .. } => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Board {
+    melds: Vec<Meld>,
+}
+
+impl Board {
+    pub fn from_melds(melds: impl IntoIterator<Item = TileSet>) -> Result<Self, BoardError> {
+        let mut board = Self::default();
+        for meld in melds {
+            board.add(meld)?;
+        }
+        Ok(board)
+    }
+
+    pub fn melds(&self) -> &[Meld] {
+        &self.melds
+    }
+
+    pub fn meld_sets(&self) -> Vec<TileSet> {
+        self.melds.iter().map(|meld| meld.tiles()).collect()
+    }
+
+    pub fn tiles(&self) -> TileSet {
+        self.melds
+            .iter()
+            .map(|meld| meld.tiles())
+            .fold(TileSet::default(), Add::add)
+    }
+
+    pub fn add(&mut self, tiles: TileSet) -> Result<(), BoardError> {
+        self.place(Meld::new(tiles)?)
+    }
+
+    pub fn place(&mut self, meld: Meld) -> Result<(), BoardError> {
+        self.tiles().checked_add(meld.tiles())?;
+        self.melds.push(meld);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<Meld, BoardError> {
+        self.get(index)?;
+        Ok(self.melds.remove(index))
+    }
+
+    fn get(&self, index: usize) -> Result<Meld, BoardError> {
+        self.melds
+            .get(index)
+            .copied()
+            .ok_or(BoardError::NoMeld(index))
+    }
+
+    // the piece from `at` upwards becomes a run of its own
+    pub fn split_run(&mut self, index: usize, at: Value) -> Result<(), BoardError> {
+        let Meld::Run { color, low, tiles } = self.get(index)? else {
+            return Err(BoardError::NotARun(index));
+        };
+        let high = low.value() + size(tiles) - 1;
+        if at.value() < low.value() + SHORTEST || at.value() + SHORTEST > high + 1 {
+            return Err(BoardError::TooShort(at));
+        }
+        let reals = tiles
+            .saturating_remove(Tile::Joker)
+            .saturating_remove(Tile::Joker);
+        let lower: TileSet = real(reals)
+            .filter(|&(_, value)| value < at)
+            .map(|(color, value)| Tile::Normal { color, value })
+            .collect();
+        let jokers = at.value() - low.value() - size(lower);
+        let lower = (0..jokers).try_fold(lower, |lower, _| lower.try_add(Tile::Joker))?;
+        let left = Meld::run(color, low, lower)?;
+        let right = Meld::run(color, at, tiles.checked_sub(lower)?)?;
+        self.melds[index] = left;
+        self.melds.insert(index + 1, right);
+        Ok(())
+    }
+
+    pub fn merge_runs(&mut self, a: usize, b: usize) -> Result<(), BoardError> {
+        let (first, second) = (self.get(a)?, self.get(b)?);
+        let run = |meld, index| match meld {
+            Meld::Run { color, low, tiles } => Ok((color, low, tiles)),
+            Meld::Group { .. } => Err(BoardError::NotARun(index)),
+        };
+        let (color, low, tiles) = run(first, a)?;
+        let (other, other_low, other_tiles) = run(second, b)?;
+        let meets = |low: Value, tiles, next: Value| low.value() + size(tiles) == next.value();
+        let merged = if color != other || a == b {
+            return Err(BoardError::NotAdjacent(a, b));
+        } else if meets(low, tiles, other_low) {
+            Meld::run(color, low, tiles + other_tiles)?
+        } else if meets(other_low, other_tiles, low) {
+            Meld::run(color, other_low, tiles + other_tiles)?
+        } else {
+            return Err(BoardError::NotAdjacent(a, b));
+        };
+        self.melds[a.min(b)] = merged;
+        self.melds.remove(a.max(b));
+        Ok(())
+    }
+
+    pub fn extend_group(&mut self, index: usize, color: Color) -> Result<(), BoardError> {
+        let Meld::Group { value, tiles } = self.get(index)? else {
+            return Err(BoardError::NotAGroup(index));
+        };
+        if real(tiles).any(|(other, _)| other == color) || size(tiles) as usize >= COLORS {
+            return Err(BoardError::ColorTaken(color));
+        }
+        let tiles = tiles.try_add(Tile::Normal { color, value })?;
+        self.melds[index] = Meld::Group { value, tiles }.checked()?;
+        Ok(())
+    }
+
+    // puts `tile` where a joker was, the joker goes back to the player
+    pub fn swap_joker(&mut self, index: usize, tile: Tile) -> Result<(), BoardError> {
+        let meld = self.get(index)?;
+        if !meld.tiles().cointains(Tile::Joker) {
+            return Err(BoardError::NoJoker(index));
+        }
+        if !meld.stood_for().contains(&tile) {
+            return Err(BoardError::NotStoodFor(tile));
+        }
+        let tiles = meld.tiles().try_remove(Tile::Joker)?.try_add(tile)?;
+        self.melds[index] = match meld {
+            Meld::Run { color, low, .. } => Meld::run(color, low, tiles)?,
+            Meld::Group { value, .. } => Meld::Group { value, tiles }.checked()?,
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tile, tiles};
+
+    #[test]
+    fn melds_are_checked() {
+        assert!(Meld::new(meld!(R3..=R5)).is_ok());
+        assert!(Meld::new(tiles![R3, R4]).is_err());
+        let gapped = tiles![R3, R5];
+        assert_eq!(Meld::new(gapped), Err(BoardError::NotAMeld(gapped)));
+        let with_joker = Meld::new(gapped + Tile::Joker).unwrap();
+        assert_eq!(with_joker.stood_for(), vec![tile!(R4)]);
+        let at_the_top = Meld::new(tiles![R12, R13, J]).unwrap();
+        assert_eq!(at_the_top.stood_for(), vec![tile!(R11)]);
+    }
+
+    #[test]
+    fn runs_split_and_merge() {
+        let mut board = Board::from_melds([meld!(U1..=U7)]).unwrap();
+        assert_eq!(
+            board.split_run(0, super::value(3).unwrap()),
+            Err(BoardError::TooShort(super::value(3).unwrap()))
+        );
+        board.split_run(0, super::value(4).unwrap()).unwrap();
+        assert_eq!(board.meld_sets(), vec![meld!(U1..=U3), meld!(U4..=U7)]);
+        assert_eq!(board.merge_runs(1, 0), Ok(()));
+        assert_eq!(board.meld_sets(), vec![meld!(U1..=U7)]);
+        assert_eq!(board.tiles(), meld!(U1..=U7));
+    }
+
+    #[test]
+    fn jokers_follow_their_place_when_splitting() {
+        let mut board = Board::from_melds([tiles![R1, R2, J, R4, R5, R6]]).unwrap();
+        board.split_run(0, super::value(4).unwrap()).unwrap();
+        assert_eq!(board.meld_sets(), vec![tiles![R1, R2, J], meld!(R4..=R6)]);
+    }
+
+    #[test]
+    fn groups_grow_and_give_up_jokers() {
+        use Color::*;
+        let mut board = Board::from_melds([meld!(R9, U9, J)]).unwrap();
+        assert_eq!(board.extend_group(0, Red), Err(BoardError::ColorTaken(Red)));
+        assert_eq!(
+            board.swap_joker(0, tile!(R8)),
+            Err(BoardError::NotStoodFor(tile!(R8)))
+        );
+        assert_eq!(board.swap_joker(0, tile!(G9)), Ok(()));
+        board.extend_group(0, Black).unwrap();
+        assert_eq!(board.swap_joker(0, tile!(B9)), Err(BoardError::NoJoker(0)));
+        assert_eq!(
+            board.extend_group(0, Black),
+            Err(BoardError::ColorTaken(Black))
+        );
+        assert_eq!(board.tiles().len(), 4);
+    }
+
+    #[test]
+    fn no_third_copy_reaches_the_board() {
+        let mut board = Board::from_melds([meld!(R1..=R3), meld!(R1..=R3)]).unwrap();
+        assert_eq!(
+            board.add(meld!(R2..=R4)),
+            Err(BoardError::Tiles(TileSetError::TooManyCopies(tile!(R2))))
+        );
+        assert_eq!(board.melds().len(), 2);
+    }
+}
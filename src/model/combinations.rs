@@ -1,19 +1,80 @@
 use std::{collections::HashSet, hash::Hash};
 
-use super::{Color, Tile, TileSet, Value};
+use super::{Color, JokerEdition, PieceUniverse, Rummikub, Special, Tile, TileSet, Value};
 
 use lazy_static::lazy_static;
 
 lazy_static! {
-    static ref ALL_COMBOS: Vec<TileSet> = gen_all_combos().collect();
+    static ref RUMMIKUB: Combos = Combos::generate(&Rummikub);
+    static ref JOKER_EDITION: Combos = Combos::generate(&JokerEdition);
+}
+
+pub(super) fn rummikub() -> &'static Combos {
+    &RUMMIKUB
+}
+
+pub(super) fn joker_edition() -> &'static Combos {
+    &JOKER_EDITION
 }
 
 pub fn all_combos() -> impl Iterator<Item = TileSet> {
-    ALL_COMBOS.iter().copied()
+    RUMMIKUB.all()
+}
+
+pub fn is_combo(tiles: TileSet) -> bool {
+    RUMMIKUB.contains(tiles)
+}
+
+pub fn combos_with(tile: Tile) -> impl Iterator<Item = TileSet> {
+    RUMMIKUB.with(tile)
 }
 
-fn gen_all_combos() -> impl Iterator<Item = TileSet> {
-    dedup(jokerless_combos().flat_map(jokerized))
+// Every meld a universe allows, and the ones each piece can be part of.
+#[derive(Debug, Clone, Default)]
+pub struct Combos {
+    all: Vec<TileSet>,
+    set: HashSet<TileSet>,
+    by_tile: Vec<Vec<TileSet>>,
+}
+
+impl Combos {
+    pub fn generate<U: PieceUniverse + ?Sized>(universe: &U) -> Self {
+        let jokers = |combo: TileSet| {
+            universe
+                .jokers_in_meld(combo.len())
+                .min(universe.jokers() as usize)
+        };
+        let specials = universe.special_jokers().iter();
+        let all: Vec<_> = dedup(
+            jokerless_combos(universe.ace_high())
+                .flat_map(|combo| jokerized(combo, jokers(combo)))
+                .chain(specials.flat_map(|&special| special_combos(special))),
+        )
+        .collect();
+        let mut by_tile = vec![vec![]; Tile::SIZE];
+        for &combo in &all {
+            for tile in combo.unique_tiles() {
+                by_tile[tile.code() as usize].push(combo);
+            }
+        }
+        Self {
+            set: all.iter().copied().collect(),
+            all,
+            by_tile,
+        }
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = TileSet> + '_ {
+        self.all.iter().copied()
+    }
+
+    pub fn contains(&self, tiles: TileSet) -> bool {
+        self.set.contains(&tiles)
+    }
+
+    pub fn with(&self, tile: Tile) -> impl Iterator<Item = TileSet> + '_ {
+        self.by_tile[tile.code() as usize].iter().copied()
+    }
 }
 
 fn dedup<A: Eq + Hash + Copy>(xs: impl Iterator<Item = A>) -> impl Iterator<Item = A> {
@@ -25,21 +86,24 @@ fn dedup<A: Eq + Hash + Copy>(xs: impl Iterator<Item = A>) -> impl Iterator<Item
     .flatten()
 }
 
-fn jokerless_combos() -> impl Iterator<Item = TileSet> {
-    repeated_combos().chain(sequence_combos())
+fn jokerless_combos(ace_high: bool) -> impl Iterator<Item = TileSet> {
+    repeated_combos().chain(sequence_combos(ace_high))
 }
 
-fn jokerized(combo: TileSet) -> impl Iterator<Item = TileSet> {
+fn jokerized(combo: TileSet, jokers: usize) -> impl Iterator<Item = TileSet> {
     [combo]
         .into_iter()
-        .chain(single_joker(combo))
-        .chain(double_joker(combo))
+        .chain(single_joker(combo).filter(move |_| jokers >= 1))
+        .chain(double_joker(combo).filter(move |_| jokers >= 2))
 }
 
 fn single_joker(combo: TileSet) -> impl Iterator<Item = TileSet> {
-    combo
-        .into_iter()
-        .map(move |tile| combo.remove(tile).add(Tile::Joker))
+    combo.into_iter().filter_map(move |tile| {
+        combo
+            .try_remove(tile)
+            .and_then(|rest| rest.try_add(Tile::Joker))
+            .ok()
+    })
 }
 
 fn double_joker(combo: TileSet) -> impl Iterator<Item = TileSet> {
@@ -50,12 +114,13 @@ fn double_joker(combo: TileSet) -> impl Iterator<Item = TileSet> {
                 .into_iter()
                 .filter_map(move |t2| (t1 < t2).then(|| (t1, t2)))
         })
-        .map(move |(t1, t2)| {
+        .filter_map(move |(t1, t2)| {
             combo
-                .remove(t1)
-                .remove(t2)
-                .add(Tile::Joker)
-                .add(Tile::Joker)
+                .try_remove(t1)
+                .and_then(|combo| combo.try_remove(t2))
+                .and_then(|combo| combo.try_add(Tile::Joker))
+                .and_then(|combo| combo.try_add(Tile::Joker))
+                .ok()
         })
 }
 
@@ -75,12 +140,15 @@ fn repeated(value: Value, exclude: Option<Color>) -> TileSet {
         .collect()
 }
 
-fn sequence_combos() -> impl Iterator<Item = TileSet> {
-    Color::all().flat_map(color_sequences)
+fn sequence_combos(ace_high: bool) -> impl Iterator<Item = TileSet> {
+    Color::all().flat_map(move |color| {
+        let high = ace_high_sequences(color).filter(move |_| ace_high);
+        color_sequences(color).chain(high)
+    })
 }
 
 fn color_sequences(color: Color) -> impl Iterator<Item = TileSet> {
-    (1..11).flat_map(move |start| {
+    (1..=11).flat_map(move |start| {
         (start + 2..=13).map(move |end| {
             (start..=end)
                 .filter_map(Value::from_code)
@@ -89,3 +157,87 @@ fn color_sequences(color: Color) -> impl Iterator<Item = TileSet> {
         })
     })
 }
+
+// the ace after the king, with no way round to the two
+fn ace_high_sequences(color: Color) -> impl Iterator<Item = TileSet> {
+    (2..=12).map(move |start| {
+        (start..=13)
+            .chain([1])
+            .filter_map(Value::from_code)
+            .map(move |value| Tile::Normal { color, value })
+            .collect()
+    })
+}
+
+// Special jokers never share a meld with a joker or with each other.
+fn special_combos(special: Special) -> Box<dyn Iterator<Item = TileSet>> {
+    let tile = Tile::Special(special);
+    match special {
+        // at least two tiles on either side of the mirror
+        Special::Mirror => Box::new(Color::all().flat_map(move |color| {
+            (1..=12).flat_map(move |low| {
+                (low + 1..=13).map(move |high| {
+                    let side = run(color, low, high);
+                    side + side + tile
+                })
+            })
+        })),
+        // at least two tiles on either side too, the joker stands for `at`
+        Special::ColorChange => Box::new(Color::all().flat_map(move |from| {
+            Color::all()
+                .filter(move |&to| to != from)
+                .flat_map(move |to| {
+                    (3..=11).flat_map(move |at| {
+                        (1..at - 1).flat_map(move |low| {
+                            (at + 2..=13).map(move |high| {
+                                run(from, low, at - 1) + tile + run(to, at + 1, high)
+                            })
+                        })
+                    })
+                })
+        })),
+        Special::Duplicate => Box::new(
+            Tile::all()
+                .filter(|&copied| copied != Tile::Joker)
+                .map(move |copied| TileSet::default() + copied + copied + tile),
+        ),
+    }
+}
+
+fn run(color: Color, low: u8, high: u8) -> TileSet {
+    (low..=high)
+        .filter_map(|value| Value::from_code(value as u64))
+        .map(|value| Tile::Normal { color, value })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tile, tiles};
+
+    #[test]
+    fn combos() {
+        assert!(is_combo(meld!(R1..=R3)));
+        assert!(is_combo(meld!(B11..=B13)));
+        assert!(is_combo(meld!(R7, G7, B7, U7)));
+        assert!(is_combo(tiles![U4, J, U6]));
+        assert!(is_combo(tiles![R9, J, J]));
+        assert!(!is_combo(tiles![R12, R13, R1]));
+        assert!(!is_combo(tiles![R7, R7, B7]));
+        assert!(combos_with(tile!(G5)).all(|combo| combo.cointains(tile!(G5))));
+        assert!(combos_with(tile!(G5)).any(|combo| combo == meld!(G3..=G5)));
+    }
+
+    #[test]
+    fn runs_reach_thirteen() {
+        for run in [
+            meld!(R11..=R13),
+            meld!(G11..=G13),
+            meld!(B11..=B13),
+            meld!(U11..=U13),
+        ] {
+            assert!(is_combo(run));
+        }
+    }
+}
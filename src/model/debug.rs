@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
-use crate::model::{all_combos, TileSet};
+use crate::model::{all_combos, CacheStats, TileSet};
 
 use super::Tile;
 
-pub fn debug_info() -> Vec<[String; 2]> {
-    [
+pub fn debug_info(cache: Option<CacheStats>) -> Vec<[String; 2]> {
+    let mut info: Vec<_> = [
         item("Combination count", all_combos().count()),
         item("Assignment count", all_combos().flatten().count()),
         item(
@@ -36,7 +36,20 @@ pub fn debug_info() -> Vec<[String; 2]> {
             count.values().copied().min().unwrap_or(0)
         }),
     ]
-    .into()
+    .into();
+    if let Some(cache) = cache {
+        info.extend([
+            item(
+                "Transposition hit rate",
+                format!("{:.1}%", 100.0 * cache.hit_rate()),
+            ),
+            item("Transposition hits", cache.hits),
+            item("Transposition misses", cache.misses),
+            item("Transposition entries", cache.entries),
+            item("Transposition evictions", cache.evictions),
+        ]);
+    }
+    info
 }
 
 fn item<A: ToString, B: ToString>(a: A, b: B) -> [String; 2] {
@@ -0,0 +1,117 @@
+use std::iter::once;
+
+use super::{combos_with, Board, PieceUniverse, Rummikub, Tile, TileSet, TileSetError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrawOdds {
+    pub tile: Tile,
+    pub copies: u8,
+    pub probability: f64,
+    pub enables_play: bool,
+}
+
+pub fn full_deck() -> TileSet {
+    Rummikub.deck()
+}
+
+// more copies on the rack and board than the deck holds is an error
+pub fn unseen(rack: TileSet, board: TileSet) -> Result<TileSet, TileSetError> {
+    full_deck().checked_sub(rack)?.checked_sub(board)
+}
+
+// Every unseen tile is equally likely to be the next one drawn, whether it
+// lies in the pile or on an opponent's rack.
+pub fn drawn_within(unseen: usize, copies: usize, turns: usize) -> f64 {
+    let missed = (0..turns.min(unseen)).fold(1.0, |p, i| {
+        p * unseen.saturating_sub(copies + i) as f64 / (unseen - i) as f64
+    });
+    1.0 - missed
+}
+
+// The drawn tile lays a meld with rack tiles, either a new one or added on to
+// a meld already on the board.
+pub fn enables_play(rack: TileSet, board: &Board, tile: Tile) -> bool {
+    let Ok(hand) = rack.try_add(tile) else {
+        return false;
+    };
+    once(TileSet::default())
+        .chain(board.meld_sets())
+        .any(|meld| {
+            combos_with(tile)
+                .filter_map(|combo| combo.checked_sub(meld).ok())
+                .any(|added| added.amount(tile) > rack.amount(tile) && added <= hand)
+        })
+}
+
+pub fn draw_odds(
+    rack: TileSet,
+    board: &Board,
+    turns: usize,
+) -> Result<Vec<DrawOdds>, TileSetError> {
+    let unseen = unseen(rack, board.tiles())?;
+    let total = unseen.len();
+    let odds = unseen
+        .unique_tiles()
+        .map(|tile| {
+            let copies = unseen.amount(tile);
+            DrawOdds {
+                tile,
+                copies,
+                probability: drawn_within(total, copies as usize, turns),
+                enables_play: enables_play(rack, board, tile),
+            }
+        })
+        .collect();
+    Ok(odds)
+}
+
+pub fn play_probability(rack: TileSet, board: &Board, turns: usize) -> Result<f64, TileSetError> {
+    let odds = draw_odds(rack, board, turns)?;
+    let total = odds.iter().map(|odds| odds.copies as usize).sum();
+    let useful = odds
+        .iter()
+        .filter(|odds| odds.enables_play)
+        .map(|odds| odds.copies as usize)
+        .sum();
+    Ok(drawn_within(total, useful, turns))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tile, tiles};
+
+    #[test]
+    fn drawing_chances() {
+        assert_eq!(drawn_within(10, 0, 3), 0.0);
+        assert_eq!(drawn_within(10, 10, 1), 1.0);
+        assert_eq!(drawn_within(4, 1, 1), 0.25);
+        assert_eq!(drawn_within(4, 1, 2), 0.5);
+        // more turns than tiles draws them all
+        assert_eq!(drawn_within(3, 1, 5), 1.0);
+    }
+
+    #[test]
+    fn plays_with_the_rack_or_the_board() {
+        let empty = Board::default();
+        assert!(enables_play(tiles![R3, R4], &empty, tile!(R5)));
+        assert!(!enables_play(tiles![R3, R4], &empty, tile!(R7)));
+
+        let board = Board::from_melds([meld!(G7, B7, U7), meld!(U9..=U11)]).unwrap();
+        assert!(!enables_play(tiles![U1], &empty, tile!(R7)));
+        assert!(enables_play(tiles![U1], &board, tile!(R7)));
+        assert!(enables_play(tiles![U1], &board, tile!(U12)));
+        assert!(!enables_play(tiles![U1], &board, tile!(U13)));
+        assert!(enables_play(tiles![U13], &board, tile!(U12)));
+    }
+
+    #[test]
+    fn too_many_copies_are_reported() {
+        let board = Board::from_melds([meld!(R3..=R5), meld!(R3..=R5)]).unwrap();
+        assert_eq!(
+            unseen(tiles![R4], board.tiles()),
+            Err(TileSetError::NotPresent(tile!(R4)))
+        );
+        assert!(draw_odds(tiles![R4], &board, 1).is_err());
+    }
+}
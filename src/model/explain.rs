@@ -0,0 +1,103 @@
+use std::fmt::Display;
+
+use super::{Solver, TileSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    // tiles with no combination inside the pool at all
+    Orphans(TileSet),
+    // tiles that can't all be covered, though any one fewer could be
+    Core(TileSet),
+}
+
+impl Conflict {
+    pub fn tiles(self) -> TileSet {
+        match self {
+            Conflict::Orphans(tiles) | Conflict::Core(tiles) => tiles,
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = |tiles: TileSet| {
+            let names: Vec<_> = tiles.into_iter().map(|tile| format!("{tile:?}")).collect();
+            names.join(", ")
+        };
+        match *self {
+            Conflict::Orphans(tiles) if tiles.into_iter().nth(1).is_none() => {
+                write!(f, "{} has no combination within the pool", names(tiles))
+            }
+            Conflict::Orphans(tiles) => {
+                write!(f, "{} have no combination within the pool", names(tiles))
+            }
+            Conflict::Core(tiles) => write!(f, "{} can't all be placed together", names(tiles)),
+        }
+    }
+}
+
+// Why the required tiles can't all be placed in the solver's melds, the
+// optional ones helping where they can: a board with the rack, or a board with
+// the tiles chosen to play.
+pub fn explain<S: Solver + ?Sized>(
+    solver: &S,
+    required: TileSet,
+    optional: TileSet,
+) -> Option<Conflict> {
+    let arrangeable = |required, optional| solver.solve(required, optional).is_some();
+    let pool = required + optional;
+    let combos = solver.combos();
+    let orphans = required.filter(|tile| !combos.with(tile).any(|combo| combo <= pool));
+    if !orphans.is_empty() {
+        return Some(Conflict::Orphans(orphans));
+    }
+    if arrangeable(required, optional) {
+        return None;
+    }
+    // a tile leaves the core by becoming optional: it stays in the pool for the
+    // others, but no longer has to be covered itself
+    let core = required.into_iter().fold(required, |core, tile| {
+        let rest = core.saturating_remove(tile);
+        if arrangeable(rest, pool - rest) {
+            core
+        } else {
+            rest
+        }
+    });
+    Some(Conflict::Core(core))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::solver::LinksSolver;
+    use crate::model::{meld, tiles, Rummikub};
+
+    #[test]
+    fn orphans() {
+        let solver = LinksSolver::new(Rummikub);
+        let board = meld!(R3..=R5) + tiles![B13];
+        let conflict = explain(&solver, board, tiles![U1]);
+        assert_eq!(conflict, Some(Conflict::Orphans(tiles![B13])));
+        assert_eq!(
+            conflict.unwrap().to_string(),
+            "B13 has no combination within the pool"
+        );
+        let rack = tiles![B11, B12];
+        assert_eq!(explain(&solver, board, rack), None);
+    }
+
+    #[test]
+    fn minimal_core() {
+        let solver = LinksSolver::new(Rummikub);
+        // R4 fits either run or group but can't serve both
+        let board = meld!(R2..=R4) + tiles![U4, B4] + meld!(G7..=G9);
+        // R3 only fits R2-R4 and U4 only the group of fours
+        let core = tiles![R3, U4];
+        assert_eq!(
+            explain(&solver, board, TileSet::default()),
+            Some(Conflict::Core(core))
+        );
+        assert_eq!(explain(&solver, board, tiles![G4]), None);
+    }
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+use super::{all_combos, partition::partition, Tile, TileSet, TileSetError};
+use crate::utils::rng::Rng;
+
+pub const STARTING_RACK: usize = 14;
+const TRACKED_DRAWS: usize = 3;
+const ATTEMPTS_PER_SAMPLE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    Draw { player: usize },
+    Play { player: usize, meld: TileSet },
+    Pass { player: usize },
+}
+
+// A pass means the rack held no meld at that moment. Since then the
+// opponent drew `drawn_since` unknown tiles and laid `played_since`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NoMeld {
+    drawn_since: usize,
+    played_since: TileSet,
+}
+
+impl NoMeld {
+    // any of the tiles held now or laid since may be one of the draws
+    fn holds(&self, rack: TileSet) -> bool {
+        unmeldable(rack + self.played_since, self.drawn_since)
+    }
+}
+
+fn unmeldable(tiles: TileSet, drops: usize) -> bool {
+    let Some(combo) = all_combos().find(|&combo| combo <= tiles) else {
+        return true;
+    };
+    drops > 0
+        && combo
+            .unique_tiles()
+            .any(|tile| unmeldable(tiles.saturating_remove(tile), drops - 1))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Opponent {
+    pub rack_size: usize,
+    pub played: TileSet,
+    pub passes: usize,
+    constraints: Vec<NoMeld>,
+}
+
+impl Default for Opponent {
+    fn default() -> Self {
+        Self {
+            rack_size: STARTING_RACK,
+            played: TileSet::default(),
+            passes: 0,
+            constraints: vec![],
+        }
+    }
+}
+
+impl Opponent {
+    pub fn admits(&self, rack: TileSet) -> bool {
+        self.constraints
+            .iter()
+            .all(|constraint| constraint.holds(rack))
+    }
+
+    fn sample(&self, pool: &[Tile], rng: &mut Rng) -> Option<TileSet> {
+        (0..ATTEMPTS_PER_SAMPLE)
+            .map(|_| rng.sample(pool, self.rack_size).into_iter().collect())
+            .find(|&rack| self.admits(rack))
+    }
+
+    fn draw(&mut self) {
+        self.rack_size += 1;
+        for constraint in &mut self.constraints {
+            constraint.drawn_since += 1;
+        }
+        self.constraints
+            .retain(|constraint| constraint.drawn_since <= TRACKED_DRAWS);
+    }
+
+    fn play(&mut self, meld: TileSet) {
+        self.rack_size = self.rack_size.saturating_sub(meld.len());
+        self.played += meld;
+        for constraint in &mut self.constraints {
+            constraint.played_since += meld;
+        }
+    }
+
+    fn pass(&mut self) {
+        self.passes += 1;
+        self.constraints.push(NoMeld {
+            drawn_since: 0,
+            played_since: TileSet::default(),
+        });
+        self.draw();
+    }
+}
+
+// the opponents' racks and the shuffled pile
+pub type Deal = (Vec<TileSet>, Vec<Tile>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inference {
+    unseen: TileSet,
+    opponents: Vec<Opponent>,
+}
+
+impl Inference {
+    pub fn new(unseen: TileSet, opponents: usize) -> Self {
+        Self {
+            unseen,
+            opponents: vec![Opponent::default(); opponents],
+        }
+    }
+
+    pub fn replay<'a>(
+        unseen: TileSet,
+        opponents: usize,
+        log: impl IntoIterator<Item = &'a Event>,
+    ) -> Result<Self, TileSetError> {
+        let mut inference = Self::new(unseen, opponents);
+        for &event in log {
+            inference.observe(event)?;
+        }
+        Ok(inference)
+    }
+
+    pub fn opponents(&self) -> &[Opponent] {
+        &self.opponents
+    }
+
+    // a meld with tiles that are already accounted for is rejected
+    pub fn observe(&mut self, event: Event) -> Result<(), TileSetError> {
+        match event {
+            Event::Draw { player } => self.opponents[player].draw(),
+            Event::Pass { player } => self.opponents[player].pass(),
+            Event::Play { player, meld } => {
+                self.unseen = self.unseen.checked_sub(meld)?;
+                self.opponents[player].play(meld);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sample_racks(&self, player: usize, samples: usize, rng: &mut Rng) -> Vec<TileSet> {
+        let opponent = &self.opponents[player];
+        let pool: Vec<_> = self.unseen.into_iter().collect();
+        (0..samples)
+            .filter_map(|_| opponent.sample(&pool, rng))
+            .collect()
+    }
+
+    // None when some opponent's rack can't be sampled
+    pub fn deal(&self, rng: &mut Rng) -> Result<Option<Deal>, TileSetError> {
+        let mut pool = self.unseen;
+        let mut racks = vec![];
+        for opponent in &self.opponents {
+            let Some(rack) = opponent.sample(&pool.into_iter().collect::<Vec<_>>(), rng) else {
+                return Ok(None);
+            };
+            pool = pool.checked_sub(rack)?;
+            racks.push(rack);
+        }
+        let pile: Vec<_> = pool.into_iter().collect();
+        Ok(Some((racks, rng.sample(&pile, pile.len()))))
+    }
+
+    pub fn go_out_chance(&self, player: usize, samples: usize, rng: &mut Rng) -> Option<f64> {
+        let racks = self.sample_racks(player, samples, rng);
+        if racks.is_empty() {
+            return None;
+        }
+        let out = racks
+            .iter()
+            .filter(|&&rack| partition(rack).is_some())
+            .count();
+        Some(out as f64 / racks.len() as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::draw::full_deck;
+    use crate::model::{meld, tile, tiles};
+
+    #[test]
+    fn a_pass_rules_out_melds() {
+        let mut inference = Inference::new(full_deck(), 1);
+        inference.observe(Event::Pass { player: 0 }).unwrap();
+        let opponent = &inference.opponents()[0];
+        assert_eq!(opponent.rack_size, STARTING_RACK + 1);
+        // the tile drawn after passing may have completed one meld, not two
+        assert!(opponent.admits(tiles![R1, R2, R3, B9]));
+        assert!(!opponent.admits(meld!(R1..=R3) + meld!(G5..=G7)));
+
+        // tiles laid since count as held when the pass happened
+        inference
+            .observe(Event::Play {
+                player: 0,
+                meld: meld!(U4..=U6),
+            })
+            .unwrap();
+        let opponent = &inference.opponents()[0];
+        assert!(opponent.admits(tiles![U9, B9]));
+        assert!(!opponent.admits(meld!(G5..=G7)));
+    }
+
+    #[test]
+    fn impossible_plays_are_reported() {
+        let unseen = full_deck() - tiles![R5, R5];
+        let mut inference = Inference::new(unseen, 2);
+        let play = Event::Play {
+            player: 1,
+            meld: meld!(R4..=R6),
+        };
+        assert_eq!(
+            inference.observe(play),
+            Err(TileSetError::NotPresent(tile!(R5)))
+        );
+        assert!(Inference::replay(unseen, 2, &[Event::Draw { player: 0 }, play]).is_err());
+    }
+
+    #[test]
+    fn go_out_chance_is_a_probability() {
+        let melds = meld!(R1..=R7) + meld!(G1..=G7);
+        let chance = |unseen| Inference::new(unseen, 1).go_out_chance(0, 20, &mut Rng::seeded(7));
+        assert_eq!(chance(melds), Some(1.0));
+        let scattered = tiles![R1, R4, R7, R10, R13, G2, G5, G8, G11, B3, B6, B9, B12, U1];
+        assert_eq!(chance(scattered), Some(0.0));
+
+        let anything = chance(full_deck()).unwrap();
+        assert!((0.0..=1.0).contains(&anything));
+        assert_eq!(chance(full_deck()), Some(anything));
+    }
+}
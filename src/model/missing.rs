@@ -0,0 +1,135 @@
+use std::collections::{HashMap, HashSet};
+
+use super::solver::LinksSolver;
+use super::{Combos, PieceUniverse, Solver, Tile, TileSet, TileSetError};
+
+pub const MAX_MISSING: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Missing {
+    pub tile: Tile,
+    pub needed: u8,
+    pub unseen: u8,
+}
+
+// The fewest unseen tiles of the universe's deck that would let the whole rack
+// be played, the likeliest to turn up among equally small ones. Some(empty)
+// when it can be played already, None when it would take more than
+// MAX_MISSING draws, which is as far as the search goes.
+pub fn missing_tiles<U: PieceUniverse>(
+    universe: U,
+    board: TileSet,
+    rack: TileSet,
+) -> Result<Option<Vec<Missing>>, TileSetError> {
+    let unseen = universe.deck().checked_sub(rack)?.checked_sub(board)?;
+    let solver = LinksSolver::new(&universe);
+    let mut left = HashMap::new();
+    let drawn = (0..=MAX_MISSING).find_map(|depth| {
+        let mut finder = Finder {
+            board,
+            rack,
+            unseen,
+            combos: universe.combos(),
+            solver: &solver,
+            left: &mut left,
+            visited: HashSet::new(),
+            found: vec![],
+        };
+        finder.search(TileSet::default(), depth);
+        // equally many tiles are drawn, so the most ways to pick them wins
+        let odds = |drawn: &TileSet| unseen.ways_to_pick(*drawn);
+        finder.found.into_iter().max_by_key(odds)
+    });
+    let Some(drawn) = drawn else {
+        return Ok(None);
+    };
+    let missing = drawn.unique_tiles().map(|tile| Missing {
+        tile,
+        needed: drawn.amount(tile),
+        unseen: unseen.amount(tile),
+    });
+    Ok(Some(missing.collect()))
+}
+
+struct Finder<'a> {
+    board: TileSet,
+    rack: TileSet,
+    unseen: TileSet,
+    combos: &'a Combos,
+    solver: &'a dyn Solver,
+    // what each draw leaves over, kept from one depth to the next
+    left: &'a mut HashMap<TileSet, TileSet>,
+    visited: HashSet<TileSet>,
+    found: Vec<TileSet>,
+}
+
+impl Finder<'_> {
+    // Every left-over tile has to end up in some combination. One the pool
+    // completes already says nothing about the draw, but then another left-over
+    // tile's may, so all of them are branched on.
+    fn search(&mut self, drawn: TileSet, budget: usize) {
+        if !self.visited.insert(drawn) {
+            return;
+        }
+        let hand = self.rack + drawn;
+        let (board, solver) = (self.board, self.solver);
+        let left = *self.left.entry(drawn).or_insert_with(|| {
+            let played = solver.solve(board, hand).map(|solution| solution.played);
+            hand - played.unwrap_or_default()
+        });
+        if left.is_empty() {
+            self.found.push(drawn);
+            return;
+        }
+        let pool = self.board + hand;
+        let needs: Vec<_> = left
+            .unique_tiles()
+            .flat_map(|tile| self.combos.with(tile))
+            .map(|combo| combo - pool)
+            .filter(|need| !need.is_empty() && need.len() <= budget)
+            .filter(|&need| need <= self.unseen - drawn)
+            .collect();
+        for need in needs {
+            self.search(drawn + need, budget - need.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tile, tiles, Rummikub};
+
+    #[test]
+    fn finds_the_fewest_draws() {
+        let board = meld!(R1..=R3);
+        let rack = tiles![B5, B6];
+        assert_eq!(missing_tiles(Rummikub, board, board), Ok(Some(vec![])));
+
+        let missing = missing_tiles(Rummikub, board, rack).unwrap().unwrap();
+        assert_eq!(missing.len(), 1);
+        let [Missing {
+            tile: drawn,
+            needed: 1,
+            unseen: 2,
+        }] = missing[..]
+        else {
+            panic!("{missing:?}");
+        };
+        assert!([tile!(B4), tile!(B7), tile!(J)].contains(&drawn));
+
+        let pair = tiles![U10, U10];
+        assert_eq!(missing_tiles(Rummikub, board, pair), Ok(None));
+    }
+
+    #[test]
+    fn branches_on_every_left_over_tile() {
+        // U2 fits combinations the pool completes already, only R8's say what
+        // to draw
+        let board = meld!(R2, G2, B2, U2) + meld!(R10, G10, J) + meld!(B13, U13, J);
+        let rack = tiles![U2, R8];
+        let missing = missing_tiles(Rummikub, board, rack).unwrap().unwrap();
+        let needed: u8 = missing.iter().map(|missing| missing.needed).sum();
+        assert_eq!(needed, 3, "{missing:?}");
+    }
+}
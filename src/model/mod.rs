@@ -1,13 +1,42 @@
+mod analysis;
+mod board;
 mod combinations;
 // mod solve;
 mod tile;
 mod tileset;
 mod debug;
-#[allow(unused)]
+mod draw;
+mod explain;
+mod inference;
+mod missing;
+#[cfg(test)]
+mod notation;
+mod okey;
+mod partition;
+mod rack;
+mod simulation;
+mod rearrange;
 mod solver;
+mod turn;
+mod universe;
 
-pub use combinations::all_combos;
-pub use tileset::TileSet;
-pub use tile::{Tile, Color, Value};
+pub use analysis::{best_draw, near_melds, splits};
+pub use board::{Board, BoardError, Meld};
+pub use combinations::{all_combos, combos_with, is_combo, Combos};
+pub use tileset::{TileSet, TileSetError};
+pub use tile::{Tile, Color, Special, Value};
 pub use debug::debug_info;
+pub use draw::{draw_odds, play_probability, unseen};
+pub use explain::{explain, Conflict};
+pub use inference::{Event, Inference};
+pub use missing::{missing_tiles, Missing, MAX_MISSING};
+pub use okey::{Okey, OkeyHand};
+#[cfg(test)]
+pub(crate) use notation::{meld, tile, tiles};
+pub use rack::{Arrangement, Rack};
+pub use rearrange::{plan, untouched};
+pub use simulation::{rank_plays, Evaluation};
+pub use solver::{Backend, CacheStats, Constraints, Rules, Solution, Solver};
+pub use turn::{BrowserClock, Ending, Penalty, Turn, PENALTY_DRAWS, TURN_TIME};
+pub use universe::{JokerEdition, PieceUniverse, Rummikub, Universe};
 
@@ -0,0 +1,254 @@
+use super::{Color, Special, Tile, Value};
+
+// Tiles are written the way Tile's Debug prints them: a colour letter and a
+// value, R5 or U12, J for a joker, or M, C and D for the mirror, colour change
+// and duplicate jokers. The macros check what they are given
+// while compiling, so a typo like R14 or X3 or a third R1 doesn't build.
+
+macro_rules! tile {
+    ($tile:ident) => {
+        const { $crate::model::notation::parse(stringify!($tile)) }
+    };
+}
+
+macro_rules! tiles {
+    ($($tile:ident),* $(,)?) => {{
+        const {
+            $crate::model::notation::check_copies(&[$($crate::model::tile!($tile)),*])
+        };
+        [$($crate::model::tile!($tile)),*]
+            .into_iter()
+            .collect::<$crate::model::TileSet>()
+    }};
+}
+
+// `meld!(R3..=R6)` is a run, `meld!(B7, U7, J)` a group. Runs with jokers are
+// listed in order, `meld!(R3, J, R5)`.
+macro_rules! meld {
+    ($low:ident ..= $high:ident) => {
+        $crate::model::notation::run(const {
+            $crate::model::notation::check_run(
+                $crate::model::tile!($low),
+                $crate::model::tile!($high),
+            )
+        })
+    };
+    ($($tile:ident),+ $(,)?) => {{
+        const {
+            $crate::model::notation::check_meld(&[$($crate::model::tile!($tile)),+])
+        };
+        $crate::model::tiles![$($tile),+]
+    }};
+}
+
+pub(crate) use {meld, tile, tiles};
+
+pub const fn parse(notation: &str) -> Tile {
+    let (color, value) = match notation.as_bytes() {
+        [b'J'] => return Tile::Joker,
+        [b'M'] => return Tile::Special(Special::Mirror),
+        [b'C'] => return Tile::Special(Special::ColorChange),
+        [b'D'] => return Tile::Special(Special::Duplicate),
+        [color, value @ ..] => (*color, value),
+        [] => panic!("a tile needs a colour and a value"),
+    };
+    let color = match color {
+        b'R' => Color::Red,
+        b'G' => Color::Green,
+        b'B' => Color::Black,
+        b'U' => Color::Blue,
+        _ => panic!("tiles start with R, G, B or U, or are a joker"),
+    };
+    let value = match value {
+        [digit @ b'1'..=b'9'] => *digit - b'0',
+        [b'1', digit @ b'0'..=b'3'] => 10 + *digit - b'0',
+        _ => panic!("tile values go from 1 to 13"),
+    };
+    match Value::from_code(value as u64) {
+        Some(value) => Tile::Normal { color, value },
+        None => panic!("tile values go from 1 to 13"),
+    }
+}
+
+pub const fn check_run(low: Tile, high: Tile) -> (Tile, Tile) {
+    let (
+        Tile::Normal { color, value: from },
+        Tile::Normal {
+            color: other,
+            value: to,
+        },
+    ) = (low, high)
+    else {
+        panic!("a run is written between its two end tiles");
+    };
+    if color as u8 != other as u8 {
+        panic!("a run keeps to one colour");
+    }
+    if to.value() < from.value() + 2 {
+        panic!("a run has three tiles or more, lowest first");
+    }
+    (low, high)
+}
+
+pub fn run((low, high): (Tile, Tile)) -> super::TileSet {
+    (low.code()..=high.code())
+        .step_by(4)
+        .filter_map(Tile::from_code)
+        .collect()
+}
+
+pub const fn check_copies(tiles: &[Tile]) {
+    let mut copies = [0u8; Tile::SIZE];
+    let mut at = 0;
+    while at < tiles.len() {
+        let code = tiles[at].code() as usize;
+        copies[code] += 1;
+        if copies[code] > 2 {
+            panic!("there are two copies of every tile");
+        }
+        at += 1;
+    }
+}
+
+// Real tiles of one colour are a run, anything else has to be a group.
+pub const fn check_meld(tiles: &[Tile]) {
+    let mut color = None;
+    let mut mixed = false;
+    let mut at = 0;
+    while at < tiles.len() {
+        if let Tile::Normal { color: own, .. } = tiles[at] {
+            if let Some(color) = color {
+                mixed |= own as u8 != color as u8;
+            }
+            color = Some(own);
+        }
+        at += 1;
+    }
+    match color {
+        Some(_) if !mixed && real_tiles(tiles) >= 2 => check_listed_run(tiles),
+        _ => check_group(tiles),
+    }
+}
+
+const fn real_tiles(tiles: &[Tile]) -> usize {
+    let mut count = 0;
+    let mut at = 0;
+    while at < tiles.len() {
+        if let Tile::Normal { .. } = tiles[at] {
+            count += 1;
+        }
+        at += 1;
+    }
+    count
+}
+
+// every real tile sits where its value puts it, jokers fill the rest
+const fn check_listed_run(tiles: &[Tile]) {
+    if tiles.len() < 3 || tiles.len() > 13 {
+        panic!("a run has three tiles or more");
+    }
+    let mut low = None;
+    let mut at = 0;
+    while at < tiles.len() {
+        if let Tile::Normal { value, .. } = tiles[at] {
+            let start = value.value() as i32 - at as i32;
+            if let Some(low) = low {
+                if start != low {
+                    panic!("a run is listed lowest first, with jokers in its gaps");
+                }
+            }
+            low = Some(start);
+        }
+        at += 1;
+    }
+    if let Some(low) = low {
+        if low < 1 || low + tiles.len() as i32 - 1 > 13 {
+            panic!("a run stays between 1 and 13");
+        }
+    }
+}
+
+pub const fn check_group(tiles: &[Tile]) {
+    if tiles.len() < 3 || tiles.len() > 4 {
+        panic!("a group has three or four tiles");
+    }
+    let mut value = None;
+    let mut colors = 0u8;
+    let mut at = 0;
+    while at < tiles.len() {
+        if let Tile::Normal { color, value: own } = tiles[at] {
+            if let Some(value) = value {
+                if own.value() != value {
+                    panic!("a group keeps to one value");
+                }
+            }
+            if colors & 1 << color as u8 != 0 {
+                panic!("a group has every colour once");
+            }
+            value = Some(own.value());
+            colors |= 1 << color as u8;
+        }
+        at += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::TileSet;
+
+    #[test]
+    fn notation_matches_debug() {
+        let five = tile!(R5);
+        assert_eq!(
+            five,
+            Tile::Normal {
+                color: Color::Red,
+                value: Value::from_code(5).unwrap(),
+            }
+        );
+        assert_eq!(format!("{:?}", tile!(U12)), "U12");
+        assert_eq!(tiles![R5, U12, J, J].len(), 4);
+        assert_eq!(tiles![J, J].jokers(), 2);
+        assert_eq!(tiles![], TileSet::default());
+        for special in [tile!(M), tile!(C), tile!(D)] {
+            assert_eq!(Tile::from_code(special.code()), Some(special));
+        }
+        assert_eq!(format!("{:?}", tile!(C)), "C");
+    }
+
+    #[test]
+    fn melds() {
+        assert_eq!(meld!(G11..=G13), tiles![G11, G12, G13]);
+        assert_eq!(meld!(B7, U7, J), tiles![B7, U7, J]);
+        assert_eq!(meld!(R3, J, R5), tiles![R3, R5, J]);
+        assert_eq!(meld!(J, R12, R13), tiles![R12, R13, J]);
+    }
+
+    // The macros run these checks in const blocks, so each panic here is a
+    // build error for the notation it was given.
+    fn rejected<T>(check: impl FnOnce() -> T + std::panic::UnwindSafe) -> String {
+        let Err(payload) = std::panic::catch_unwind(check) else {
+            panic!("the notation was accepted");
+        };
+        match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(payload) => *payload.downcast::<String>().unwrap(),
+        }
+    }
+
+    #[test]
+    fn invalid_notation_does_not_build() {
+        assert!(rejected(|| parse("R14")).contains("1 to 13"));
+        assert!(rejected(|| parse("X3")).contains("start with"));
+        let (r1, r4, r5, j) = (tile!(R1), tile!(R4), tile!(R5), tile!(J));
+        assert!(rejected(|| check_copies(&[r1, r1, r1])).contains("two copies"));
+        assert!(rejected(|| check_run(r4, r5)).contains("three tiles"));
+        assert!(rejected(|| check_meld(&[r1, j, r5])).contains("jokers in its gaps"));
+        assert!(rejected(|| check_meld(&[r4, r5])).contains("three tiles"));
+        assert!(rejected(|| check_meld(&[r1, tile!(G1), tile!(G1)])).contains("every colour"));
+        // the joker would have to be R14
+        let (r12, r13) = (tile!(R12), tile!(R13));
+        assert!(rejected(|| check_meld(&[r12, r13, j])).contains("between 1 and 13"));
+    }
+}
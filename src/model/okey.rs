@@ -0,0 +1,232 @@
+use lazy_static::lazy_static;
+
+use super::solver::LinksSolver;
+use super::{Combos, PieceUniverse, Solver, Tile, TileSet, Value};
+
+const HAND: usize = 14;
+const DRAWN: usize = HAND + 1;
+const PAIRS: usize = 7;
+
+lazy_static! {
+    // melds are made of what tiles play as, so every indicator shares them
+    static ref COMBOS: Combos = Combos::generate(&Okey {
+        indicator: Tile::Joker,
+        okey: Tile::Joker,
+    });
+}
+
+// The tile after the indicator in its colour, 13 followed by 1, is the okey:
+// both copies are wild. The two false jokers play as the okey's face, so in a
+// hand Tile::Joker stands for a false joker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Okey {
+    indicator: Tile,
+    okey: Tile,
+}
+
+impl Okey {
+    pub fn new(indicator: Tile) -> Option<Self> {
+        let Tile::Normal { color, value } = indicator else {
+            return None;
+        };
+        let value = Value::from_code(value.value() as u64 % 13 + 1)?;
+        Some(Self {
+            indicator,
+            okey: Tile::Normal { color, value },
+        })
+    }
+
+    pub fn indicator(&self) -> Tile {
+        self.indicator
+    }
+
+    pub fn okey(&self) -> Tile {
+        self.okey
+    }
+
+    // Swaps the okeys with the false jokers, which turns a hand into what its
+    // tiles play as, and back again.
+    pub fn played_as(&self, tiles: TileSet) -> TileSet {
+        let rest = tiles.filter(|tile| tile != self.okey && tile != Tile::Joker);
+        let okeys = (0..tiles.amount(self.okey)).map(|_| Tile::Joker);
+        let false_jokers = (0..tiles.jokers()).map(|_| self.okey);
+        okeys
+            .chain(false_jokers)
+            .fold(rest, |tiles, tile| tiles + tile)
+    }
+
+    // The closest hand to a win: fourteen tiles in melds or in seven pairs,
+    // with the best tile discarded from a fifteenth drawn one. A hand never
+    // holds more than that, so bigger ones are None.
+    pub fn evaluate(&self, hand: TileSet) -> Option<OkeyHand> {
+        match hand.len() {
+            ..=HAND => Some(self.arrange(hand)),
+            DRAWN => hand
+                .unique_tiles()
+                .map(|discard| OkeyHand {
+                    discard: Some(discard),
+                    ..self.arrange(hand.saturating_remove(discard))
+                })
+                .min_by_key(|evaluated| evaluated.left.len()),
+            _ => None,
+        }
+    }
+
+    fn arrange(&self, hand: TileSet) -> OkeyHand {
+        let played = self.played_as(hand);
+        let melds = LinksSolver::new(self)
+            .solve(TileSet::default(), played)
+            .map(|solution| solution.melds)
+            .unwrap_or_default();
+        let pairs = pairs(played);
+        let melded = self.hand(hand, melds, false);
+        let paired = self.hand(hand, pairs, true);
+        if paired.left.len() < melded.left.len() {
+            paired
+        } else {
+            melded
+        }
+    }
+
+    fn hand(&self, hand: TileSet, melds: Vec<TileSet>, pairs: bool) -> OkeyHand {
+        let melds: Vec<_> = melds.into_iter().map(|meld| self.played_as(meld)).collect();
+        let arranged = melds
+            .iter()
+            .fold(TileSet::default(), |all, &meld| all + meld);
+        OkeyHand {
+            melds,
+            pairs,
+            left: hand - arranged,
+            discard: None,
+        }
+    }
+}
+
+// identical pairs first, then the wild okeys pair up with what is left
+fn pairs(tiles: TileSet) -> Vec<TileSet> {
+    let real = tiles.filter(|tile| tile != Tile::Joker);
+    let twice = real.filter(|tile| real.amount(tile) == 2);
+    let mut pairs: Vec<_> = twice
+        .unique_tiles()
+        .map(|tile| TileSet::default() + tile + tile)
+        .collect();
+    let mut jokers = (0..tiles.jokers()).map(|_| Tile::Joker);
+    for single in (real - twice).unique_tiles() {
+        let Some(joker) = jokers.next() else {
+            break;
+        };
+        pairs.push(TileSet::default() + single + joker);
+    }
+    if jokers.len() == 2 {
+        pairs.push(TileSet::default() + Tile::Joker + Tile::Joker);
+    }
+    pairs.truncate(PAIRS);
+    pairs
+}
+
+impl PieceUniverse for Okey {
+    fn name(&self) -> &'static str {
+        "Okey"
+    }
+
+    fn copies(&self) -> u8 {
+        2
+    }
+
+    fn jokers(&self) -> u8 {
+        2
+    }
+
+    fn ace_high(&self) -> bool {
+        true
+    }
+
+    fn jokers_in_meld(&self, _: usize) -> usize {
+        2
+    }
+
+    fn combos(&self) -> &Combos {
+        &COMBOS
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OkeyHand {
+    pub melds: Vec<TileSet>,
+    pub pairs: bool,
+    pub left: TileSet,
+    pub discard: Option<Tile>,
+}
+
+impl OkeyHand {
+    pub fn is_winning(&self) -> bool {
+        let arranged: usize = self.melds.iter().map(|meld| meld.len()).sum();
+        self.left.is_empty() && arranged == HAND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tile, tiles};
+
+    #[test]
+    fn the_okey_follows_the_indicator() {
+        let okey = Okey::new(tile!(R13)).unwrap();
+        assert_eq!(okey.okey(), tile!(R1));
+        assert!(Okey::new(tile!(J)).is_none());
+
+        let hand = tiles![R1, J, J, U5];
+        assert_eq!(okey.played_as(hand), tiles![J, R1, R1, U5]);
+        assert_eq!(okey.played_as(okey.played_as(hand)), hand);
+        assert!(okey.combos().contains(meld!(G11..=G13) + tile!(G1)));
+        assert!(!okey.combos().contains(tiles![G13, G1, G2]));
+    }
+
+    #[test]
+    fn wins_with_melds() {
+        // B6 is the okey and stands in for R11
+        let okey = Okey::new(tile!(B5)).unwrap();
+        let hand = meld!(R1..=R4) + tiles![U11, U12, U13, U1, G7, B7, U7, R9, R10, B6];
+        let evaluated = okey.evaluate(hand).unwrap();
+        assert!(evaluated.is_winning(), "{evaluated:?}");
+        assert!(!evaluated.pairs);
+
+        // a drawn fifteenth tile is thrown away
+        let evaluated = okey.evaluate(hand + tile!(G2)).unwrap();
+        assert!(evaluated.is_winning(), "{evaluated:?}");
+        assert_eq!(evaluated.discard, Some(tile!(G2)));
+        assert_eq!(okey.evaluate(hand + tiles![G2, G3]), None);
+    }
+
+    #[test]
+    fn losing_hands_keep_the_most_in_melds() {
+        let okey = Okey::new(tile!(B5)).unwrap();
+        let loose = tiles![R9, G2, B10, U4];
+        let hand = meld!(R1..=R4) + tiles![U11, U12, U13, G7, B7, U7] + loose;
+        let evaluated = okey.evaluate(hand).unwrap();
+        assert!(!evaluated.is_winning());
+        assert!(!evaluated.pairs);
+        assert_eq!(evaluated.left, loose);
+
+        // R5 joins the run, so one of the loose tiles goes
+        let evaluated = okey.evaluate(hand + tile!(R5)).unwrap();
+        assert!(!evaluated.is_winning());
+        assert!(evaluated.melds.contains(&meld!(R1..=R5)));
+        let discard = evaluated.discard.unwrap();
+        assert_eq!(evaluated.left + discard, loose);
+    }
+
+    #[test]
+    fn wins_with_seven_pairs() {
+        let okey = Okey::new(tile!(B5)).unwrap();
+        let pairs = tiles![R1, R1, G4, G4, U9, U9, B12, B12, R7, R7, G13, G13];
+        let evaluated = okey.evaluate(pairs + tiles![U3, B6]).unwrap();
+        assert!(evaluated.is_winning(), "{evaluated:?}");
+        assert!(evaluated.pairs);
+
+        let evaluated = okey.evaluate(pairs + tiles![U3, G3]).unwrap();
+        assert!(!evaluated.is_winning());
+        assert_eq!(evaluated.left, tiles![U3, G3]);
+    }
+}
@@ -0,0 +1,14 @@
+use super::{combos_with, TileSet};
+
+pub fn partition(tiles: TileSet) -> Option<Vec<TileSet>> {
+    let Some(first) = tiles.into_iter().next() else {
+        return Some(vec![]);
+    };
+    combos_with(first)
+        .filter(|&combo| combo <= tiles)
+        .find_map(|combo| {
+            let mut rest = partition(tiles - combo)?;
+            rest.push(combo);
+            Some(rest)
+        })
+}
@@ -0,0 +1,136 @@
+use super::{splits, Color, PieceUniverse, Rummikub, Tile, TileSet, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Arrangement {
+    #[default]
+    Runs,
+    Groups,
+    MeldsFirst,
+}
+
+impl Arrangement {
+    pub fn all() -> impl Iterator<Item = Arrangement> {
+        [
+            Arrangement::Runs,
+            Arrangement::Groups,
+            Arrangement::MeldsFirst,
+        ]
+        .into_iter()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Arrangement::Runs => "Runs",
+            Arrangement::Groups => "Groups",
+            Arrangement::MeldsFirst => "Melds first",
+        }
+    }
+}
+
+// Tiles come out in clusters the way players lay out a rack: one per colour,
+// one per value, or every complete meld on its own and the rest by colour.
+// Jokers, special ones too, always end up together at the end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rack {
+    tiles: TileSet,
+}
+
+impl Rack {
+    pub fn new(tiles: TileSet) -> Self {
+        Self { tiles }
+    }
+
+    pub fn arranged(self, by: Arrangement) -> Vec<Vec<Tile>> {
+        match by {
+            Arrangement::Runs => clusters(self.tiles, |color, _| color as usize),
+            Arrangement::Groups => clusters(self.tiles, |_, value| value.value() as usize),
+            Arrangement::MeldsFirst => {
+                let melds = splits(Rummikub.combos(), self.tiles)
+                    .into_iter()
+                    .next()
+                    .unwrap_or_default();
+                let rest = melds.iter().fold(self.tiles, |rest, &meld| rest - meld);
+                let mut arranged: Vec<_> = melds
+                    .into_iter()
+                    .map(|meld| Rack::new(meld).sorted(Arrangement::Runs))
+                    .collect();
+                arranged.extend(Rack::new(rest).arranged(Arrangement::Runs));
+                arranged
+            }
+        }
+    }
+
+    pub fn sorted(self, by: Arrangement) -> Vec<Tile> {
+        self.arranged(by).into_iter().flatten().collect()
+    }
+}
+
+fn clusters(tiles: TileSet, key: fn(Color, Value) -> usize) -> Vec<Vec<Tile>> {
+    let mut normal: Vec<_> = tiles
+        .into_iter()
+        .filter_map(|tile| match tile {
+            Tile::Normal { color, value } => Some((key(color, value), tile)),
+            Tile::Joker | Tile::Special(_) => None,
+        })
+        .collect();
+    normal.sort_by_key(|&(cluster, tile)| (cluster, tile));
+
+    let mut arranged: Vec<Vec<Tile>> = vec![];
+    let mut last = None;
+    for (cluster, tile) in normal {
+        match arranged.last_mut() {
+            Some(current) if last == Some(cluster) => current.push(tile),
+            _ => arranged.push(vec![tile]),
+        }
+        last = Some(cluster);
+    }
+    let jokers: Vec<_> = tiles
+        .filter(|tile| !matches!(tile, Tile::Normal { .. }))
+        .into_iter()
+        .collect();
+    if !jokers.is_empty() {
+        arranged.push(jokers);
+    }
+    arranged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{tile, tiles};
+
+    fn rack() -> Rack {
+        Rack::new(tiles![U2, R7, R2, B2, J, R5, R6])
+    }
+
+    #[test]
+    fn runs_and_groups_views() {
+        assert_eq!(
+            rack().arranged(Arrangement::Runs),
+            vec![
+                vec![tile!(R2), tile!(R5), tile!(R6), tile!(R7)],
+                vec![tile!(B2)],
+                vec![tile!(U2)],
+                vec![tile!(J)],
+            ]
+        );
+        assert_eq!(
+            rack().arranged(Arrangement::Groups),
+            vec![
+                vec![tile!(R2), tile!(B2), tile!(U2)],
+                vec![tile!(R5)],
+                vec![tile!(R6)],
+                vec![tile!(R7)],
+                vec![tile!(J)],
+            ]
+        );
+    }
+
+    #[test]
+    fn melds_come_first() {
+        let arranged = rack().arranged(Arrangement::MeldsFirst);
+        let laid: usize = arranged[..2].iter().map(Vec::len).sum();
+        assert_eq!(laid, 7, "{arranged:?}");
+        assert_eq!(rack().sorted(Arrangement::MeldsFirst).len(), 7);
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt::Display;
+
+use super::solver::JokerSwap;
+use super::{is_combo, Tile, TileSet, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    SwapJoker { meld: TileSet, tile: Tile },
+    Split { meld: TileSet, at: Value },
+    Take { tiles: TileSet, from: TileSet },
+    Append { tiles: TileSet, meld: TileSet },
+    Lay { meld: TileSet },
+}
+
+impl Step {
+    fn order(&self) -> u8 {
+        match self {
+            Step::SwapJoker { .. } => 0,
+            Step::Split { .. } => 1,
+            Step::Take { .. } => 2,
+            Step::Append { .. } => 3,
+            Step::Lay { .. } => 4,
+        }
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Step::SwapJoker { meld, tile } => JokerSwap { meld, tile }.fmt(f),
+            Step::Split { meld, at } => write!(f, "Split {meld:?} before {at}"),
+            Step::Take { tiles, from } if tiles == from => write!(f, "Pick up {from:?}"),
+            Step::Take { tiles, from } => write!(f, "Take {tiles:?} from {from:?}"),
+            Step::Append { tiles, meld } => write!(f, "Add {tiles:?} to make {meld:?}"),
+            Step::Lay { meld } => write!(f, "Lay down {meld:?}"),
+        }
+    }
+}
+
+pub fn plan(before: &[TileSet], after: &[TileSet]) -> Vec<Step> {
+    let matched = match_melds(before, after);
+    let mut pending: Vec<bool> = matched.iter().map(Option::is_none).collect();
+    let mut kept = vec![false; before.len()];
+    let mut steps = vec![];
+
+    for (&new, &old) in after.iter().zip(&matched) {
+        let Some(old) = old else { continue };
+        kept[old] = true;
+        let old = before[old];
+        let mut leaving = old - new;
+        let mut arriving = new - old;
+
+        if let Some(tile) = joker_swap(old, new) {
+            steps.push(Step::SwapJoker { meld: old, tile });
+            leaving -= Tile::Joker;
+            arriving -= tile;
+        }
+
+        if !leaving.is_empty() {
+            match split_point(old, leaving) {
+                Some(at) => {
+                    steps.push(Step::Split { meld: old, at });
+                    let piece = (0..after.len()).find(|&k| pending[k] && after[k] == leaving);
+                    if let Some(k) = piece {
+                        pending[k] = false;
+                    }
+                }
+                None => steps.push(Step::Take {
+                    tiles: leaving,
+                    from: old,
+                }),
+            }
+        }
+
+        if !arriving.is_empty() {
+            steps.push(Step::Append {
+                tiles: arriving,
+                meld: new,
+            });
+        }
+    }
+
+    let dropped = before.iter().zip(&kept).filter(|(_, &kept)| !kept);
+    steps.extend(dropped.map(|(&old, _)| Step::Take {
+        tiles: old,
+        from: old,
+    }));
+
+    let laid = after.iter().zip(&pending).filter(|(_, &pending)| pending);
+    steps.extend(laid.map(|(&meld, _)| Step::Lay { meld }));
+
+    steps.sort_by_key(Step::order);
+    steps
+}
+
+pub fn untouched(before: &[TileSet], after: &[TileSet]) -> usize {
+    let mut after = after.to_vec();
+    let mut found = |meld: &TileSet| {
+        let index = after.iter().position(|other| other == meld);
+        index.map(|index| after.swap_remove(index)).is_some()
+    };
+    before.iter().filter(|meld| found(meld)).count()
+}
+
+// Every tile a new meld keeps from an old one is a tile that needn't move, so
+// the pairing sharing the most tiles overall leaves the smallest symmetric
+// difference to plan for. Melds left over pair with nothing.
+fn match_melds(before: &[TileSet], after: &[TileSet]) -> Vec<Option<usize>> {
+    let shared = |i: usize, j: usize| match (before.get(i), after.get(j)) {
+        (Some(&old), Some(&new)) => old.intersection(new).len(),
+        _ => 0,
+    };
+    let size = before.len().max(after.len());
+    let cost: Vec<Vec<i64>> = (0..size)
+        .map(|i| (0..size).map(|j| -(shared(i, j) as i64)).collect())
+        .collect();
+
+    let mut matched = vec![None; after.len()];
+    for (i, j) in assign(&cost).into_iter().enumerate() {
+        if shared(i, j) > 0 {
+            matched[j] = Some(i);
+        }
+    }
+    matched
+}
+
+// The Hungarian method on a square cost matrix, the column given to each row
+// in an assignment of the least total cost.
+fn assign(cost: &[Vec<i64>]) -> Vec<usize> {
+    let size = cost.len();
+    // rows and columns count from 1 here, column 0 stands for the row being added
+    let mut row_potential = vec![0; size + 1];
+    let mut column_potential = vec![0; size + 1];
+    let mut owner = vec![0; size + 1];
+    let mut way = vec![0; size + 1];
+    for row in 1..=size {
+        owner[0] = row;
+        let mut column = 0;
+        let mut slack = vec![i64::MAX; size + 1];
+        let mut visited = vec![false; size + 1];
+        while owner[column] != 0 {
+            visited[column] = true;
+            let from = owner[column];
+            let mut delta = i64::MAX;
+            let mut next = 0;
+            for to in 1..=size {
+                if visited[to] {
+                    continue;
+                }
+                let reduced = cost[from - 1][to - 1] - row_potential[from] - column_potential[to];
+                if reduced < slack[to] {
+                    slack[to] = reduced;
+                    way[to] = column;
+                }
+                if slack[to] < delta {
+                    delta = slack[to];
+                    next = to;
+                }
+            }
+            for to in 0..=size {
+                if visited[to] {
+                    row_potential[owner[to]] += delta;
+                    column_potential[to] -= delta;
+                } else {
+                    slack[to] -= delta;
+                }
+            }
+            column = next;
+        }
+        while column != 0 {
+            let previous = way[column];
+            owner[column] = owner[previous];
+            column = previous;
+        }
+    }
+
+    let mut assigned = vec![0; size];
+    for column in 1..=size {
+        assigned[owner[column] - 1] = column - 1;
+    }
+    assigned
+}
+
+fn joker_swap(old: TileSet, new: TileSet) -> Option<Tile> {
+    if old.jokers() <= new.jokers() {
+        return None;
+    }
+    let freed = old.try_remove(Tile::Joker).ok()?;
+    (new - old)
+        .unique_tiles()
+        .find(|&tile| freed.try_add(tile).is_ok_and(is_combo))
+}
+
+fn values(tiles: TileSet) -> impl Iterator<Item = Value> {
+    tiles.into_iter().filter_map(|tile| match tile {
+        Tile::Normal { value, .. } => Some(value),
+        Tile::Joker | Tile::Special(_) => None,
+    })
+}
+
+fn is_run(meld: TileSet) -> bool {
+    let mut colors = meld.into_iter().filter_map(|tile| match tile {
+        Tile::Normal { color, .. } => Some(color),
+        Tile::Joker | Tile::Special(_) => None,
+    });
+    let Some(first) = colors.next() else {
+        return false;
+    };
+    colors.all(|color| color == first)
+}
+
+fn split_point(meld: TileSet, leaving: TileSet) -> Option<Value> {
+    if leaving.cointains(Tile::Joker) || !is_run(meld) || !is_combo(leaving) {
+        return None;
+    }
+    let kept = meld - leaving;
+    let low = values(leaving).min()?;
+    let high = values(leaving).max()?;
+    if values(kept).all(|value| value < low) {
+        Some(low)
+    } else if values(kept).all(|value| value > high) {
+        values(kept).min()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tile, tiles};
+
+    #[test]
+    fn unchanged_melds_need_no_steps() {
+        let board = [meld!(R1..=R3), meld!(G7, B7, U7)];
+        assert_eq!(plan(&board, &board), vec![]);
+        assert_eq!(untouched(&board, &board), 2);
+    }
+
+    #[test]
+    fn lays_and_appends() {
+        assert_eq!(
+            plan(&[], &[meld!(G7, B7, U7)]),
+            vec![Step::Lay {
+                meld: meld!(G7, B7, U7)
+            }]
+        );
+        assert_eq!(
+            plan(&[meld!(R3..=R5)], &[meld!(R3..=R6)]),
+            vec![Step::Append {
+                tiles: tiles![R6],
+                meld: meld!(R3..=R6)
+            }]
+        );
+    }
+
+    #[test]
+    fn splits_runs() {
+        let before = [meld!(R3..=R8)];
+        let after = [meld!(R3..=R5), meld!(R6..=R8)];
+        assert_eq!(
+            plan(&before, &after),
+            vec![Step::Split {
+                meld: meld!(R3..=R8),
+                at: Value::from_code(6).unwrap()
+            }]
+        );
+        assert_eq!(untouched(&before, &after), 0);
+    }
+
+    #[test]
+    fn takes_from_groups() {
+        let before = [meld!(R6, G6, B6, U6)];
+        let after = [meld!(G6, B6, U6), meld!(R4..=R6)];
+        assert_eq!(
+            plan(&before, &after),
+            vec![
+                Step::Take {
+                    tiles: tiles![R6],
+                    from: meld!(R6, G6, B6, U6)
+                },
+                Step::Lay {
+                    meld: meld!(R4..=R6)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn swaps_jokers_first() {
+        let before = [tiles![R3, J, R5], meld!(U1..=U3)];
+        let after = [meld!(R3..=R5), meld!(G9, B9, J), meld!(U1..=U3)];
+        assert_eq!(
+            plan(&before, &after),
+            vec![
+                Step::SwapJoker {
+                    meld: tiles![R3, J, R5],
+                    tile: tile!(R4)
+                },
+                Step::Lay {
+                    meld: meld!(G9, B9, J)
+                },
+            ]
+        );
+        assert_eq!(untouched(&before, &after), 1);
+    }
+
+    #[test]
+    fn pairs_melds_to_share_the_most_tiles() {
+        // R2..R7 shares most with R4..R10, but splitting it and growing both
+        // halves takes fewer steps than picking R8..R10 up to rebuild it
+        let before = [meld!(R2..=R7), meld!(R8..=R10)];
+        let after = [meld!(R4..=R10), meld!(R1..=R3)];
+        assert_eq!(
+            plan(&before, &after),
+            vec![
+                Step::Split {
+                    meld: meld!(R2..=R7),
+                    at: Value::from_code(4).unwrap()
+                },
+                Step::Append {
+                    tiles: meld!(R4..=R7),
+                    meld: meld!(R4..=R10)
+                },
+                Step::Append {
+                    tiles: tiles![R1],
+                    meld: meld!(R1..=R3)
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::cmp::Reverse;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::turn::{Clock, Ending, Turn, TURN_TIME};
+use super::{
+    all_combos, is_combo, splits, unseen, Backend, Board, Event, Inference, PieceUniverse,
+    Rummikub, Tile, TileSet, TileSetError,
+};
+use crate::utils::rng::Rng;
+
+const MAX_TURNS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub board: Board,
+    pub rack: TileSet,
+    pub inference: Inference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub played: TileSet,
+    pub score: f64,
+    pub wins: f64,
+}
+
+// Each candidate is the board a play leaves behind, the position's own board
+// when the player draws instead.
+pub fn evaluate(
+    position: &Position,
+    candidates: &[Board],
+    samples: usize,
+    seed: u64,
+) -> Result<Vec<Evaluation>, TileSetError> {
+    let mut evaluations = vec![];
+    for after in candidates {
+        let played = after.tiles().checked_sub(position.board.tiles())?;
+        // every candidate is played against the same deals
+        let mut rng = Rng::seeded(seed);
+        let mut outcomes = vec![];
+        for _ in 0..samples {
+            let Some((racks, pile)) = position.inference.deal(&mut rng)? else {
+                continue;
+            };
+            outcomes.extend(playout(after.clone(), position.rack, played, racks, pile));
+        }
+        let games = outcomes.len().max(1) as f64;
+        evaluations.push(Evaluation {
+            played,
+            score: outcomes.iter().map(|&(score, _)| score as f64).sum::<f64>() / games,
+            wins: outcomes.iter().filter(|&&(_, won)| won).count() as f64 / games,
+        });
+    }
+    evaluations.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(evaluations)
+}
+
+// Ranks what the solver would play against laying only rack melds, each of
+// those melds alone, and drawing instead. Opponents' racks are sampled from
+// what the log says about them.
+pub fn rank_plays(
+    board: &Board,
+    rack: TileSet,
+    opponents: usize,
+    log: &[Event],
+    samples: usize,
+    seed: u64,
+) -> Result<Vec<Evaluation>, TileSetError> {
+    let position = Position {
+        board: board.clone(),
+        rack,
+        inference: Inference::replay(unseen(rack, board.tiles())?, opponents, log)?,
+    };
+    let lay = |melds: &[TileSet]| {
+        let mut after = board.clone();
+        melds.iter().try_for_each(|&meld| after.add(meld)).ok()?;
+        Some(after)
+    };
+    let solved = Backend::default().solver().solve(board.tiles(), rack);
+    let mut candidates: Vec<Board> = solved
+        .and_then(|solution| solution.board(board).ok())
+        .into_iter()
+        .collect();
+    candidates.extend(lay(&bot_melds(rack)));
+    let split = splits(Rummikub.combos(), rack).into_iter().next();
+    for meld in split.unwrap_or_default() {
+        candidates.extend(lay(&[meld]));
+    }
+    candidates.push(board.clone());
+    let mut unique = vec![];
+    for candidate in candidates {
+        if !unique.contains(&candidate) {
+            unique.push(candidate);
+        }
+    }
+    evaluate(&position, &unique, samples, seed)
+}
+
+fn bot_melds(rack: TileSet) -> Vec<TileSet> {
+    let mut rest = rack;
+    let mut melds = vec![];
+    while let Some(next) = all_combos()
+        .filter_map(|combo| rest.checked_sub(combo).ok())
+        .max_by_key(|&next| Reverse(next.len()))
+    {
+        melds.push(rest - next);
+        rest = next;
+    }
+    melds
+}
+
+// bots make up their minds at once, so their time never runs out
+struct BotClock;
+
+impl Clock for BotClock {
+    fn now(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+// Bots lay what melds their rack makes and then add what's left to the board's
+// melds, picking one up and laying it again with the tile.
+fn bot_turn(play: &mut Turn<BotClock>) {
+    for meld in bot_melds(play.rack()) {
+        // a failed meld is recorded by the turn and penalized at its end
+        let _ = play.lay(meld);
+    }
+    for tile in play.rack() {
+        let melds = play.board().meld_sets();
+        let Some(index) = melds
+            .iter()
+            .position(|meld| meld.try_add(tile).is_ok_and(is_combo))
+        else {
+            continue;
+        };
+        if play.edit(|board| board.remove(index)).is_ok() {
+            let _ = play.lay(melds[index] + tile);
+        }
+    }
+}
+
+fn points(tiles: TileSet) -> i32 {
+    tiles.points() as i32
+}
+
+fn playout(
+    mut board: Board,
+    rack: TileSet,
+    played: TileSet,
+    opponents: Vec<TileSet>,
+    mut pile: Vec<Tile>,
+) -> Option<(i32, bool)> {
+    let mut racks = vec![rack.checked_sub(played).ok()?];
+    racks.extend(opponents);
+    if played.is_empty() {
+        if let Some(tile) = pile.pop() {
+            racks[0] = racks[0].try_add(tile).ok()?;
+        }
+    }
+
+    let players = racks.len();
+    let mut idle = 0;
+    let mut winner = None;
+    for turn in 0..MAX_TURNS {
+        if let Some(out) = racks.iter().position(|rack| rack.is_empty()) {
+            winner = Some(out);
+            break;
+        }
+        if idle >= players {
+            break;
+        }
+        // bot turns keep the engine's rules, penalty draws included
+        let player = (turn + 1) % players;
+        let mut play = Turn::start(BotClock, TURN_TIME, board, racks[player]);
+        bot_turn(&mut play);
+        let (after, rack, ending) = play.end(&mut pile);
+        board = after;
+        racks[player] = rack;
+        idle = match ending {
+            Ending::Drew(drawn) | Ending::Penalized(_, drawn) if drawn.is_empty() => idle + 1,
+            _ => 0,
+        };
+    }
+
+    // a blocked game goes to the player with the fewest points left
+    let winner = winner.unwrap_or_else(|| {
+        (0..players)
+            .min_by_key(|&player| points(racks[player]))
+            .unwrap_or(0)
+    });
+    let score = if winner == 0 {
+        racks[1..].iter().map(|&rack| points(rack)).sum()
+    } else {
+        -points(racks[0])
+    };
+    Some((score, winner == 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tiles};
+
+    fn position() -> Position {
+        let rack = meld!(R3..=R6) + meld!(G9, B9, U9);
+        Position {
+            board: Board::default(),
+            rack,
+            inference: Inference::new(unseen(rack, TileSet::default()).unwrap(), 2),
+        }
+    }
+
+    fn laid(melds: &[TileSet]) -> Board {
+        Board::from_melds(melds.iter().copied()).unwrap()
+    }
+
+    fn ranking(position: &Position, candidates: &[Board], seed: u64) -> Vec<TileSet> {
+        evaluate(position, candidates, 20, seed)
+            .unwrap()
+            .into_iter()
+            .map(|evaluation| evaluation.played)
+            .collect()
+    }
+
+    #[test]
+    fn the_seed_fixes_the_ranking() {
+        let position = position();
+        let candidates = [
+            laid(&[meld!(R3..=R6)]),
+            laid(&[meld!(G9, B9, U9)]),
+            Board::default(),
+        ];
+        let evaluate = |seed| evaluate(&position, &candidates, 20, seed).unwrap();
+        let first = evaluate(1);
+        assert_eq!(first, evaluate(1));
+        assert!((2..40).any(|seed| evaluate(seed) != first));
+    }
+
+    #[test]
+    fn going_out_ranks_first() {
+        let position = position();
+        let candidates = [
+            Board::default(),
+            laid(&[meld!(G9, B9, U9)]),
+            laid(&[meld!(R3..=R6), meld!(G9, B9, U9)]),
+        ];
+        for seed in 0..3 {
+            assert_eq!(ranking(&position, &candidates, seed)[0], position.rack);
+        }
+    }
+
+    #[test]
+    fn blocked_winners_score_the_others_tiles() {
+        // nobody can play or draw, and the first player holds the fewest points
+        let blocked = |rack, opponent| {
+            playout(
+                Board::default(),
+                rack,
+                TileSet::default(),
+                vec![opponent],
+                vec![],
+            )
+        };
+        assert_eq!(blocked(tiles![R1], tiles![B13]), Some((13, true)));
+        assert_eq!(blocked(tiles![R9], tiles![B2]), Some((-9, false)));
+    }
+
+    #[test]
+    fn bots_play_onto_the_board() {
+        // the opponent can't meld R6 alone, but goes out adding it to R3..R5
+        let board = laid(&[meld!(R3..=R5)]);
+        let outcome = playout(
+            board,
+            tiles![R1],
+            TileSet::default(),
+            vec![tiles![R6]],
+            vec![],
+        );
+        assert_eq!(outcome, Some((-1, false)));
+    }
+
+    #[test]
+    fn ranks_the_solver_play_with_the_alternatives() {
+        let board = Board::from_melds([meld!(U3..=U5)]).unwrap();
+        let rack = meld!(R3..=R6) + tiles![U6, B12];
+        let ranked = rank_plays(&board, rack, 2, &[], 10, 5).unwrap();
+        let played: Vec<_> = ranked.iter().map(|evaluation| evaluation.played).collect();
+        assert!(played.contains(&(meld!(R3..=R6) + tiles![U6])));
+        assert!(played.contains(&meld!(R3..=R6)));
+        assert!(played.contains(&TileSet::default()));
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    }
+}
@@ -0,0 +1,189 @@
+use lazy_static::lazy_static;
+
+use super::constraints::take;
+use super::transposition::{Key, Table};
+use super::{Solution, Solver};
+use crate::model::{combos_with, Tile, TileSet};
+
+// Searches Rummikub's melds only: the fits and the transposition keys both
+// assume them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BitboardSolver;
+
+impl Solver for BitboardSolver {
+    fn solve(&self, board: TileSet, rack: TileSet) -> Option<Solution> {
+        if !(board + rack).specials().is_empty() {
+            return None;
+        }
+        let mut search = Search::default();
+        search.best(board, rack)?;
+        let melds = search.melds(board, rack);
+        Some(Solution {
+            cache: Some(search.table.stats()),
+            ..Solution::new(melds, board)
+        })
+    }
+}
+
+lazy_static! {
+    // For every tile the combinations holding it that contain no smaller one:
+    // whenever some combination fits a pool, one of these does.
+    static ref FITS: Vec<Vec<TileSet>> = (0..Tile::SIZE as u64)
+        .map(|code| Tile::from_code(code).map_or(vec![], minimal_combos_with))
+        .collect();
+}
+
+fn minimal_combos_with(tile: Tile) -> Vec<TileSet> {
+    let mut combos: Vec<_> = combos_with(tile).collect();
+    combos.sort_by_key(|combo| combo.len());
+    let mut minimal: Vec<TileSet> = vec![];
+    for combo in combos {
+        if !minimal.iter().any(|&smaller| smaller <= combo) {
+            minimal.push(combo);
+        }
+    }
+    minimal
+}
+
+fn fits(tile: Tile, pool: TileSet) -> impl Iterator<Item = TileSet> {
+    FITS[tile.code() as usize]
+        .iter()
+        .copied()
+        .filter(move |&combo| combo <= pool)
+}
+
+// The pool tiles some combination inside the pool can hold. A combination that
+// fits marks all of its tiles at once, so most tiles need no search of their own.
+fn fitting(pool: TileSet) -> TileSet {
+    let mut fit = TileSet::default();
+    for tile in pool.unique_tiles() {
+        if !fit.cointains(tile) {
+            fit += fits(tile, pool).next().unwrap_or_default();
+        }
+    }
+    fit
+}
+
+// how many copies of the tile the pool could place at once
+fn placeable(tile: Tile, pool: TileSet, fit: TileSet) -> u8 {
+    if !fit.cointains(tile) {
+        0
+    } else if pool.amount(tile) == 2
+        && !fits(tile, pool).any(|combo| fits(tile, pool - combo).next().is_some())
+    {
+        1
+    } else {
+        pool.amount(tile)
+    }
+}
+
+// Every board copy still has to fit some combination, and only rack copies
+// that fit one as well can be placed: None when the board is stuck, the rack
+// bound otherwise.
+fn bound(board: TileSet, rack: TileSet) -> Option<usize> {
+    let pool = board + rack;
+    let fit = fitting(pool);
+    pool.unique_tiles().try_fold(0, |reachable, tile| {
+        let placed = placeable(tile, pool, fit).checked_sub(board.amount(tile))?;
+        Some(reachable + placed as usize)
+    })
+}
+
+struct Move {
+    meld: Option<TileSet>,
+    board: TileSet,
+    rack: TileSet,
+    placed: usize,
+}
+
+// Covers the lowest remaining tile with every combination that fits, taking
+// board copies first, or leaves it on the rack.
+fn moves(board: TileSet, rack: TileSet, tile: Tile) -> impl Iterator<Item = Move> {
+    let melds = combos_with(tile).filter_map(move |combo| {
+        let (rest_board, rest_rack) = take(board, rack, combo)?;
+        Some(Move {
+            meld: Some(combo),
+            board: rest_board,
+            rack: rest_rack,
+            placed: (rack - rest_rack).len(),
+        })
+    });
+    // leaving the tile on the rack leaves every copy of it: placing another
+    // copy is covered by the combinations above
+    let leave = (!board.cointains(tile)).then(|| Move {
+        meld: None,
+        board,
+        rack: rack.saturating_remove(tile).saturating_remove(tile),
+        placed: 0,
+    });
+    melds.chain(leave)
+}
+
+#[derive(Default)]
+struct Search {
+    table: Table,
+}
+
+impl Search {
+    // most rack tiles that can join the board, None when it can't be arranged
+    fn best(&mut self, board: TileSet, rack: TileSet) -> Option<usize> {
+        let Some(tile) = (board + rack).into_iter().next() else {
+            return Some(0);
+        };
+        let key = Key::new(board, rack);
+        if let Some(known) = self.table.get(&key) {
+            return known;
+        }
+
+        let mut best = None;
+        if let Some(reachable) = bound(board, rack) {
+            for mv in moves(board, rack, tile) {
+                if best == Some(reachable) {
+                    break;
+                }
+                let Some(rest) = bound(mv.board, mv.rack) else {
+                    continue;
+                };
+                if best.is_some_and(|best| mv.placed + rest <= best) {
+                    continue;
+                }
+                if let Some(rest) = self.best(mv.board, mv.rack) {
+                    best = best.max(Some(mv.placed + rest));
+                }
+            }
+        }
+        self.table.insert(key, best);
+        best
+    }
+
+    fn melds(&mut self, mut board: TileSet, mut rack: TileSet) -> Vec<TileSet> {
+        let mut melds = vec![];
+        while let Some(tile) = (board + rack).into_iter().next() {
+            let Some(target) = self.best(board, rack) else {
+                break;
+            };
+            let Some(mv) = moves(board, rack, tile).find(|mv| {
+                self.best(mv.board, mv.rack)
+                    .is_some_and(|rest| mv.placed + rest == target)
+            }) else {
+                break;
+            };
+            melds.extend(mv.meld);
+            (board, rack) = (mv.board, mv.rack);
+        }
+        melds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::tiles;
+
+    #[test]
+    fn second_copies_need_a_combination_of_their_own() {
+        assert_eq!(bound(tiles![R5], tiles![R3, R4, R5]), Some(2));
+        assert_eq!(bound(tiles![R5, R5], tiles![R3, R4]), None);
+        assert_eq!(bound(tiles![R5, R5], tiles![R3, R4, R6, R7]), Some(4));
+    }
+}
@@ -0,0 +1,442 @@
+use std::fmt::Display;
+
+use super::{CacheStats, Rules, Solution, Solver};
+use crate::model::{Board, Tile, TileSet, TileSetError};
+
+// Pinned melds appear unchanged in the result, extend-only melds stay together
+// and may only grow with rack tiles, after giving up their jokers unless
+// `no_joker_swaps` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Constraints {
+    pub pinned: Vec<TileSet>,
+    pub extend_only: Vec<TileSet>,
+    pub kept: TileSet,
+    pub joker_limit: Option<u8>,
+    pub no_joker_swaps: bool,
+}
+
+impl Constraints {
+    pub fn toggle_pinned(&mut self, meld: TileSet) {
+        self.extend_only.retain(|&other| other != meld);
+        toggle(&mut self.pinned, meld);
+    }
+
+    pub fn toggle_extend_only(&mut self, meld: TileSet) {
+        self.pinned.retain(|&other| other != meld);
+        toggle(&mut self.extend_only, meld);
+    }
+
+    // The rack tiles that may be played. Keeping tiles the rack doesn't hold
+    // is an error; every joker played comes off the rack, so the limit just
+    // hides the rest.
+    pub fn playable(&self, rack: TileSet) -> Result<TileSet, TileSetError> {
+        let mut rack = rack.checked_sub(self.kept)?;
+        let limit = self.joker_limit.unwrap_or(u8::MAX);
+        while rack.amount(Tile::Joker) > limit {
+            rack = rack.try_remove(Tile::Joker)?;
+        }
+        Ok(rack)
+    }
+
+    // The melds left once every pinned meld takes one copy out, so a meld laid
+    // twice and pinned once keeps its other copy.
+    pub(super) fn unpinned(&self, melds: Vec<TileSet>) -> Vec<TileSet> {
+        let mut pinned = self.pinned.clone();
+        melds
+            .into_iter()
+            .filter(|meld| match pinned.iter().position(|other| other == meld) {
+                Some(i) => {
+                    pinned.swap_remove(i);
+                    false
+                }
+                None => true,
+            })
+            .collect()
+    }
+}
+
+fn toggle(melds: &mut Vec<TileSet>, meld: TileSet) {
+    if melds.contains(&meld) {
+        melds.retain(|&other| other != meld);
+    } else {
+        melds.push(meld);
+    }
+}
+
+// takes the meld out of the pool, board copies first
+pub(super) fn take(board: TileSet, rack: TileSet, meld: TileSet) -> Option<(TileSet, TileSet)> {
+    let from_board = meld.intersection(board);
+    let from_rack = meld - from_board;
+    Some((board - from_board, rack.checked_sub(from_rack).ok()?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JokerSwap {
+    pub meld: TileSet,
+    pub tile: Tile,
+}
+
+impl Display for JokerSwap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Swap the joker in {:?} for {:?}", self.meld, self.tile)
+    }
+}
+
+// A bare tile set doesn't say how its jokers are laid, so any arrangement of the
+// board tiles stands in for the real one.
+pub(super) fn solve_with<S: Solver + ?Sized>(
+    solver: &S,
+    board: TileSet,
+    rack: TileSet,
+    constraints: &Constraints,
+) -> Option<Solution> {
+    let layout = board
+        .cointains(Tile::Joker)
+        .then(|| solver.solve(board, TileSet::default()))
+        .flatten()
+        .and_then(|solution| Board::from_melds(solution.melds).ok())
+        .unwrap_or_default();
+    solve_laid(solver, &layout, board, rack, constraints)
+}
+
+fn solve_laid<S: Solver + ?Sized>(
+    solver: &S,
+    layout: &Board,
+    board: TileSet,
+    rack: TileSet,
+    constraints: &Constraints,
+) -> Option<Solution> {
+    let rack = constraints.playable(rack).ok()?;
+    let (mut rest_board, mut rest_rack) = (board, rack);
+    for &meld in &constraints.pinned {
+        (rest_board, rest_rack) = take(rest_board, rest_rack, meld)?;
+    }
+    let search = |board, rack| {
+        let mut search = Extensions {
+            solver,
+            swapping: !constraints.no_joker_swaps,
+            best: None,
+            melds: constraints.pinned.clone(),
+            swaps: vec![],
+        };
+        search.run(&constraints.extend_only, board, rack);
+        search.best
+    };
+    let mut best = search(rest_board, rest_rack);
+
+    // A joker in a meld the search may rearrange can also be swapped for a rack
+    // tile it stands for, which then joins the board along with the joker.
+    // Playing as much either way, the swap is what gets suggested.
+    let swappable = layout.melds().iter().filter(|meld| {
+        let tiles = meld.tiles();
+        tiles.cointains(Tile::Joker)
+            && !constraints.no_joker_swaps
+            && !constraints.pinned.contains(&tiles)
+            && !constraints.extend_only.contains(&tiles)
+    });
+    for meld in swappable {
+        for tile in meld.stood_for() {
+            let (Ok(rack), Ok(board)) = (rest_rack.try_remove(tile), rest_board.try_add(tile))
+            else {
+                continue;
+            };
+            let Some((melds, mut swaps, cache)) = search(board, rack) else {
+                continue;
+            };
+            let size = Extensions::<S>::size;
+            if best
+                .as_ref()
+                .is_none_or(|(best, ..)| size(best) <= size(&melds))
+            {
+                let meld = meld.tiles();
+                swaps.insert(0, JokerSwap { meld, tile });
+                best = Some((melds, swaps, cache));
+            }
+        }
+    }
+
+    let (melds, swaps, cache) = best?;
+    Some(Solution {
+        swaps,
+        cache,
+        ..Solution::new(melds, board)
+    })
+}
+
+// Without manipulation every board meld stays as it is or grows at its ends,
+// and new melds come from the rack alone.
+pub(super) fn solve_turn<S: Solver + ?Sized>(
+    solver: &S,
+    board: &Board,
+    rack: TileSet,
+    rules: Rules,
+    constraints: &Constraints,
+) -> Option<Solution> {
+    match rules {
+        Rules::Standard => solve_laid(solver, board, board.tiles(), rack, constraints),
+        Rules::NoManipulation => {
+            let mut constraints = constraints.clone();
+            constraints.extend_only = constraints.unpinned(board.meld_sets());
+            constraints.no_joker_swaps = true;
+            solver.solve_with(board.tiles(), rack, &constraints)
+        }
+    }
+}
+
+type Found = (Vec<TileSet>, Vec<JokerSwap>, Option<CacheStats>);
+
+struct Extensions<'a, S: ?Sized> {
+    solver: &'a S,
+    swapping: bool,
+    best: Option<Found>,
+    melds: Vec<TileSet>,
+    swaps: Vec<JokerSwap>,
+}
+
+impl<S: Solver + ?Sized> Extensions<'_, S> {
+    // every result covers the whole board, so the larger one plays more
+    fn size(melds: &[TileSet]) -> usize {
+        melds.iter().map(|meld| meld.len()).sum()
+    }
+
+    fn run(&mut self, extend_only: &[TileSet], board: TileSet, rack: TileSet) {
+        let Some((&meld, extend_only)) = extend_only.split_first() else {
+            let Some(solution) = self.solver.solve(board, rack) else {
+                return;
+            };
+            let mut melds = self.melds.clone();
+            melds.extend(solution.melds);
+            let better = self
+                .best
+                .as_ref()
+                .is_none_or(|(best, ..)| Self::size(best) < Self::size(&melds));
+            if better {
+                self.best = Some((melds, self.swaps.clone(), solution.cache));
+            }
+            return;
+        };
+        if let Some((board, rack)) = take(board, rack, meld) {
+            self.grow(meld, extend_only, board, rack);
+        }
+    }
+
+    // A joker may first be swapped for a tile it can stand for, from the rack or
+    // the rest of the board. It then has to be played this turn, which is what
+    // putting it back on the board asks of the solver.
+    fn grow(&mut self, meld: TileSet, extend_only: &[TileSet], board: TileSet, rack: TileSet) {
+        let freed = meld.try_remove(Tile::Joker).ok().filter(|_| self.swapping);
+        if let Some(freed) = freed {
+            let stand_ins = (board + rack).unique_tiles().filter_map(|tile| {
+                let swapped = freed.try_add(tile).ok()?;
+                (tile != Tile::Joker && self.solver.combos().contains(swapped))
+                    .then_some((tile, swapped))
+            });
+            for (tile, swapped) in stand_ins {
+                let Some((board, rack)) = take(board, rack, TileSet::default() + tile) else {
+                    continue;
+                };
+                let Ok(board) = board.try_add(Tile::Joker) else {
+                    continue;
+                };
+                self.swaps.push(JokerSwap { meld, tile });
+                self.grow(swapped, extend_only, board, rack);
+                self.swaps.pop();
+            }
+        }
+
+        let Some(first) = meld.into_iter().next() else {
+            return self.run(extend_only, board, rack);
+        };
+        let grown = self.solver.combos().with(first);
+        let grown = grown.filter(|&combo| meld <= combo && combo - meld <= rack);
+        for combo in grown {
+            self.melds.push(combo);
+            self.run(extend_only, board, rack - (combo - meld));
+            self.melds.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::solver::LinksSolver;
+    use crate::model::{meld, tile, tiles, Backend, Rules, Rummikub, Universe};
+
+    fn played(board: TileSet, rack: TileSet, constraints: &Constraints) -> Vec<Option<TileSet>> {
+        Backend::all()
+            .map(|backend| {
+                let solution = backend.solver().solve_with(board, rack, constraints)?;
+                assert!(constraints
+                    .pinned
+                    .iter()
+                    .all(|meld| solution.melds.contains(meld)));
+                Some(solution.played)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn kept_tiles_and_jokers_stay() {
+        let board = meld!(R3..=R5) + meld!(B7, U7, G7);
+        let rack = tiles![R6, R7, J];
+        let kept = Constraints {
+            kept: tiles![R6],
+            joker_limit: Some(0),
+            ..Constraints::default()
+        };
+        assert!(played(board, rack, &kept)
+            .iter()
+            .all(|&played| played == Some(tiles![R7])));
+    }
+
+    #[test]
+    fn only_rack_tiles_can_be_kept() {
+        let kept = Constraints {
+            kept: tiles![R6, R6],
+            ..Constraints::default()
+        };
+        let rack = tiles![R6, R7];
+        assert_eq!(
+            kept.playable(rack),
+            Err(TileSetError::NotPresent(tile!(R6)))
+        );
+        assert!(played(meld!(R3..=R5), rack, &kept)
+            .iter()
+            .all(Option::is_none));
+    }
+
+    #[test]
+    fn constraints_follow_the_universe() {
+        // Q-K-A only makes a meld with aces high, so extending it needs the
+        // card universe's combinations all the way through
+        let board = tiles![R12, R13, R1];
+        let extend = Constraints {
+            extend_only: vec![board],
+            ..Constraints::default()
+        };
+        let cards = LinksSolver::new(Universe::Cards.pieces());
+        let solution = cards.solve_with(board, tiles![R11, G5], &extend).unwrap();
+        assert_eq!(solution.played, tiles![R11]);
+        assert!(solution.melds.contains(&tiles![R11, R12, R13, R1]));
+
+        assert!(LinksSolver::new(Rummikub)
+            .solve_with(board, tiles![R11], &extend)
+            .is_none());
+    }
+
+    #[test]
+    fn pinned_melds_stay_whole() {
+        let group = meld!(B7, U7, G7);
+        let board = meld!(R3..=R5) + group;
+        let rack = tiles![R6, R7];
+        let pinned = Constraints {
+            pinned: vec![group],
+            ..Constraints::default()
+        };
+        assert!(played(board, rack, &pinned)
+            .iter()
+            .all(|&played| played == Some(rack)));
+    }
+
+    #[test]
+    fn extend_only_melds_are_not_split() {
+        let run = meld!(R4..=R7);
+        let rack = tiles![B4, U4, R8];
+        let free = played(run, rack, &Constraints::default());
+        assert!(free.iter().all(|&played| played == Some(rack)));
+        let extend_only = Constraints {
+            extend_only: vec![run],
+            ..Constraints::default()
+        };
+        assert!(played(run, rack, &extend_only)
+            .iter()
+            .all(|&played| played == Some(tiles![R8])));
+    }
+
+    #[test]
+    fn jokers_are_retrieved() {
+        let meld = tiles![R4, R5, J];
+        let rack = tiles![R6, B8, U8];
+        let extend_only = Constraints {
+            extend_only: vec![meld],
+            ..Constraints::default()
+        };
+        for backend in Backend::all() {
+            let solution = backend
+                .solver()
+                .solve_with(meld, rack, &extend_only)
+                .unwrap();
+            assert_eq!(solution.played, rack);
+            let swap = JokerSwap {
+                meld,
+                tile: tile!(R6),
+            };
+            assert_eq!(solution.swaps, vec![swap]);
+            assert_eq!(swap.to_string(), "Swap the joker in [R4, R5, J] for R6");
+        }
+    }
+
+    #[test]
+    fn free_jokers_are_swapped_for_rack_tiles() {
+        let meld = tiles![R3, J, R5];
+        let board = Board::from_melds([meld]).unwrap();
+        let rack = tiles![R4, G4, B4];
+        let swap = JokerSwap {
+            meld,
+            tile: tile!(R4),
+        };
+        for backend in Backend::all() {
+            let solver = backend.solver();
+            let free = solver.solve_with(meld, rack, &Constraints::default());
+            let turn = solver.solve_turn(&board, rack, Rules::Standard, &Constraints::default());
+            for solution in [free.unwrap(), turn.unwrap()] {
+                assert_eq!(solution.played, rack);
+                assert_eq!(solution.swaps, vec![swap]);
+            }
+        }
+    }
+
+    #[test]
+    fn beginners_only_extend() {
+        let run = tiles![R4, R5, J];
+        let group = meld!(B9, U9, G9);
+        let rack = tiles![R6, B8, U8, R9];
+        let board = Board::from_melds([run, group]).unwrap();
+        let turn = |rules| {
+            let solutions = Backend::all().map(|backend| {
+                backend
+                    .solver()
+                    .solve_turn(&board, rack, rules, &Constraints::default())
+                    .unwrap()
+            });
+            solutions
+                .map(|solution| solution.played)
+                .collect::<Vec<_>>()
+        };
+        assert!(turn(Rules::Standard).iter().all(|&played| played == rack));
+        let extended = tiles![R6, R9];
+        assert!(turn(Rules::NoManipulation)
+            .iter()
+            .all(|&played| played == extended));
+    }
+
+    #[test]
+    fn pinning_one_copy_leaves_the_other_to_extend_only() {
+        // R6 could only come out of the unpinned copy by manipulating it
+        let run = meld!(R3..=R6);
+        let board = Board::from_melds([run, run]).unwrap();
+        let pinned = Constraints {
+            pinned: vec![run],
+            ..Constraints::default()
+        };
+        assert_eq!(pinned.unpinned(board.meld_sets()), vec![run]);
+        for backend in Backend::all() {
+            let solution = backend
+                .solver()
+                .solve_turn(&board, tiles![G6, B6], Rules::NoManipulation, &pinned)
+                .unwrap();
+            assert_eq!(solution.played, TileSet::default());
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::cell::Cell;
+use std::iter::successors;
+
+use super::{Constraints, Rules, Solution, Solver};
+use crate::model::{Board, TileSet};
+
+// Each try is a whole solve, and a big board has more subsets of melds to try
+// than there is time for.
+const MAX_SOLVES: usize = 100;
+
+// Leaves alone as many board melds as the best play allows, then lets as many
+// of the disturbed ones only grow. Both counts deepen one meld at a time, so
+// the first arrangement found is a least disruptive one. Once MAX_SOLVES tries
+// find nothing gentler, the best play is kept as it is.
+pub(super) fn solve_gently<S: Solver + ?Sized>(
+    solver: &S,
+    board: &Board,
+    rack: TileSet,
+    rules: Rules,
+    constraints: &Constraints,
+) -> Option<Solution> {
+    let best = solver.solve_turn(board, rack, rules, constraints)?;
+    let target = best.played_count();
+    let solves = Cell::new(0);
+    let solve = |tried: &Constraints| {
+        solves.set(solves.get() + 1);
+        solver
+            .solve_turn(board, rack, rules, tried)
+            .filter(|found| found.played_count() == target)
+    };
+    let free = constraints.unpinned(board.meld_sets());
+    let picked = |mask: u64| {
+        let melds = free.iter().enumerate();
+        melds.filter_map(move |(i, &meld)| (mask >> i & 1 == 1).then_some(meld))
+    };
+
+    for disturbed in 0..=free.len() {
+        for touched in subsets(free.len(), disturbed) {
+            if solves.get() >= MAX_SOLVES {
+                return Some(best);
+            }
+            let mut alone = constraints.clone();
+            for meld in picked(!touched) {
+                // an extend-only meld left alone is pinned instead
+                if let Some(i) = alone.extend_only.iter().position(|&other| other == meld) {
+                    alone.extend_only.remove(i);
+                }
+                alone.pinned.push(meld);
+            }
+            if solve(&alone).is_none() {
+                continue;
+            }
+            // extend-only melds that were disturbed already only grow
+            let mut growing: Vec<_> = picked(touched).collect();
+            for meld in &alone.extend_only {
+                if let Some(i) = growing.iter().position(|other| other == meld) {
+                    growing.remove(i);
+                }
+            }
+            for split in 0..=growing.len() {
+                for broken in subsets(growing.len(), split) {
+                    if solves.get() >= MAX_SOLVES {
+                        return Some(best);
+                    }
+                    let mut tried = alone.clone();
+                    let kept = growing.iter().enumerate();
+                    let kept = kept.filter_map(|(i, &meld)| (broken >> i & 1 == 0).then_some(meld));
+                    tried.extend_only.extend(kept);
+                    if let Some(found) = solve(&tried) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+    }
+    Some(best)
+}
+
+// every set of `size` out of `count` indices as a bitmask, in increasing order
+fn subsets(count: usize, size: usize) -> impl Iterator<Item = u64> {
+    let first = (1u64 << size) - 1;
+    let next = move |&set: &u64| {
+        if set == 0 {
+            return None;
+        }
+        let low = set & set.wrapping_neg();
+        let carried = set + low;
+        Some((((carried ^ set) >> 2) / low) | carried)
+    };
+    successors(Some(first), next).take_while(move |&set| set < 1 << count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tiles, Backend, Color, Tile, Value};
+
+    #[test]
+    fn extends_rather_than_splits() {
+        let rack = tiles![R7];
+        let group = meld!(B9, U9, G9);
+        let board = Board::from_melds([meld!(R1..=R6), group]).unwrap();
+        for backend in Backend::all() {
+            let solution = backend
+                .solver()
+                .solve_gently(&board, rack, Rules::Standard, &Constraints::default())
+                .unwrap();
+            assert_eq!(solution.played, rack);
+            assert!(solution.melds.contains(&meld!(R1..=R7)));
+            assert!(solution.melds.contains(&group));
+        }
+    }
+
+    #[test]
+    fn disturbs_the_fewest_melds() {
+        // pinning the run first, as the largest, would push R7 and R8 into
+        // both groups instead of extending the run alone
+        let run = meld!(R4..=R6);
+        let sevens = meld!(G7, B7, U7);
+        let eights = meld!(G8, B8, U8);
+        let board = Board::from_melds([run, sevens, eights]).unwrap();
+        for backend in Backend::all() {
+            let solution = backend
+                .solver()
+                .solve_gently(
+                    &board,
+                    tiles![R7, R8],
+                    Rules::Standard,
+                    &Constraints::default(),
+                )
+                .unwrap();
+            assert_eq!(solution.played, tiles![R7, R8]);
+            assert!(solution.melds.contains(&meld!(R4..=R8)));
+            assert!(solution.melds.contains(&sevens));
+            assert!(solution.melds.contains(&eights));
+        }
+    }
+
+    // counts the tries solve_gently makes through solve_turn
+    struct Counted {
+        solver: Box<dyn Solver>,
+        turns: Cell<usize>,
+    }
+
+    impl Solver for Counted {
+        fn solve(&self, board: TileSet, rack: TileSet) -> Option<Solution> {
+            self.solver.solve(board, rack)
+        }
+
+        fn solve_turn(
+            &self,
+            board: &Board,
+            rack: TileSet,
+            rules: Rules,
+            constraints: &Constraints,
+        ) -> Option<Solution> {
+            self.turns.set(self.turns.get() + 1);
+            self.solver.solve_turn(board, rack, rules, constraints)
+        }
+    }
+
+    #[test]
+    fn big_boards_fall_back_to_the_best_play() {
+        // two runs of every colour up to 9, and a rack growing the last black
+        // and blue ones: a pair of melds well past MAX_SOLVES tries
+        let run = |color, low| {
+            (low..low + 3)
+                .map(|value| Tile::Normal {
+                    color,
+                    value: Value::from_code(value).unwrap(),
+                })
+                .collect::<TileSet>()
+        };
+        let melds = Color::all().flat_map(|color| [1, 4, 7, 1, 4, 7].map(|low| run(color, low)));
+        let board = Board::from_melds(melds).unwrap();
+        assert_eq!(board.melds().len(), 24);
+        let rack = tiles![B10, U10];
+        for backend in Backend::all() {
+            let counted = Counted {
+                solver: backend.solver(),
+                turns: Cell::new(0),
+            };
+            let solution = counted
+                .solve_gently(&board, rack, Rules::Standard, &Constraints::default())
+                .unwrap();
+            assert_eq!(solution.played, rack);
+            // the best play comes first, then the tries
+            assert_eq!(counted.turns.get(), MAX_SOLVES + 1);
+        }
+    }
+
+    #[test]
+    fn subsets_come_in_order() {
+        assert_eq!(subsets(4, 2).collect::<Vec<_>>(), [3, 5, 6, 9, 10, 12]);
+        assert_eq!(subsets(3, 0).collect::<Vec<_>>(), [0]);
+        assert_eq!(subsets(3, 3).collect::<Vec<_>>(), [7]);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::iter::repeat_n;
+
+use super::{Solution, Solver};
+use crate::model::{Color, Tile, TileSet, Value};
+
+// Sweeps values 1..=13 keeping, for every colour, the lengths of at most two
+// open runs (capped at three), after van Rijn, Takes & Vis. Jokers are spent as
+// stand-ins for a tile of some colour at the current value. Only knows
+// Rummikub's melds, whatever universe the tiles came from.
+
+const COLORS: usize = 4;
+const LONG: u8 = 3;
+const COPIES: u8 = 2;
+
+type Runs = [[u8; 2]; COLORS];
+type Moves = [Move; COLORS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+enum Slot {
+    #[default]
+    Idle,
+    Start,
+    Extend,
+    End,
+}
+
+impl Slot {
+    fn options(len: u8) -> &'static [Slot] {
+        match len {
+            0 => &[Slot::Idle, Slot::Start],
+            1 | 2 => &[Slot::Extend],
+            _ => &[Slot::Extend, Slot::End],
+        }
+    }
+
+    fn takes_tile(self) -> bool {
+        matches!(self, Slot::Start | Slot::Extend)
+    }
+
+    fn apply(self, len: u8) -> u8 {
+        match self {
+            Slot::Idle | Slot::End => 0,
+            Slot::Start => 1,
+            Slot::Extend => (len + 1).min(LONG),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Move {
+    real: u8,
+    jokers: u8,
+    slots: [Slot; 2],
+}
+
+impl Move {
+    fn grouped(&self) -> u8 {
+        let used = self.slots.iter().filter(|slot| slot.takes_tile()).count() as u8;
+        self.real + self.jokers - used
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    value: u8,
+    runs: Runs,
+    jokers: u8,
+}
+
+impl State {
+    fn after(&self, moves: &Moves) -> Self {
+        let mut runs = self.runs;
+        for (run, mv) in runs.iter_mut().zip(moves) {
+            *run = [mv.slots[0].apply(run[0]), mv.slots[1].apply(run[1])];
+            run.sort();
+        }
+        Self {
+            value: self.value + 1,
+            runs,
+            jokers: self.jokers + moves.iter().map(|mv| mv.jokers).sum::<u8>(),
+        }
+    }
+}
+
+fn groups(moves: &Moves) -> Option<[u8; 2]> {
+    let mask = |count: u8| {
+        (0..COLORS)
+            .filter(|&color| moves[color].grouped() == count)
+            .fold(0u8, |mask, color| mask | 1 << color)
+    };
+    let (singles, doubles) = (mask(1), mask(2));
+    (0..1u8 << COLORS)
+        .filter(|&sub| sub & !singles == 0)
+        .map(|sub| [doubles | sub, doubles | (singles & !sub)])
+        .find(|groups| {
+            groups
+                .iter()
+                .all(|group| group.count_ones() == 0 || group.count_ones() >= 3)
+        })
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DynamicSolver;
+
+impl Solver for DynamicSolver {
+    fn solve(&self, board: TileSet, rack: TileSet) -> Option<Solution> {
+        // a special joker fits no Rummikub meld, so it can't be placed
+        if !(board + rack).specials().is_empty() {
+            return None;
+        }
+        let mut sweep = Sweep {
+            board,
+            rack,
+            colors: Color::all().collect(),
+            memo: HashMap::new(),
+        };
+        let start = State {
+            value: 1,
+            runs: [[0; 2]; COLORS],
+            jokers: 0,
+        };
+        sweep.best(start)?;
+        Some(Solution::new(sweep.melds(start), board))
+    }
+}
+
+struct Sweep {
+    board: TileSet,
+    rack: TileSet,
+    colors: Vec<Color>,
+    memo: HashMap<State, Option<(u32, Moves)>>,
+}
+
+impl Sweep {
+    fn tile(&self, color: usize, value: u8) -> Option<Tile> {
+        let value = Value::from_code(value as u64)?;
+        Some(Tile::Normal {
+            color: self.colors[color],
+            value,
+        })
+    }
+
+    fn best(&mut self, state: State) -> Option<u32> {
+        if state.value > 13 {
+            let closed = state
+                .runs
+                .iter()
+                .flatten()
+                .all(|&len| len == 0 || len == LONG);
+            let board_jokers = self.board.jokers();
+            let spare = state.jokers.checked_sub(board_jokers);
+            return spare.filter(|_| closed).map(u32::from);
+        }
+        if let Some(known) = self.memo.get(&state) {
+            return known.map(|(score, _)| score);
+        }
+
+        let mut moves = vec![];
+        self.moves(state, 0, [Move::default(); COLORS], 0, &mut moves);
+        let mut best: Option<(u32, Moves)> = None;
+        for (moves, gain) in moves {
+            let Some(rest) = self.best(state.after(&moves)) else {
+                continue;
+            };
+            if best.is_none_or(|(score, _)| score < gain + rest) {
+                best = Some((gain + rest, moves));
+            }
+        }
+        self.memo.insert(state, best);
+        best.map(|(score, _)| score)
+    }
+
+    fn moves(
+        &self,
+        state: State,
+        color: usize,
+        moves: Moves,
+        gain: u32,
+        found: &mut Vec<(Moves, u32)>,
+    ) {
+        if color == COLORS {
+            if groups(&moves).is_some() {
+                found.push((moves, gain));
+            }
+            return;
+        }
+        let Some(tile) = self.tile(color, state.value) else {
+            return;
+        };
+        let (board, rack) = (self.board.amount(tile), self.rack.amount(tile));
+        let jokers = self.board.jokers() + self.rack.jokers();
+        let spent = state.jokers + moves.iter().map(|mv| mv.jokers).sum::<u8>();
+        let [a, b] = state.runs[color];
+
+        for real in board..=(board + rack).min(COPIES) {
+            for extra in 0..=(COPIES - real).min(jokers - spent) {
+                for &first in Slot::options(a) {
+                    for &second in Slot::options(b) {
+                        // equal slots are interchangeable
+                        if a == b && first > second {
+                            continue;
+                        }
+                        let mv = Move {
+                            real,
+                            jokers: extra,
+                            slots: [first, second],
+                        };
+                        let used = mv.slots.iter().filter(|slot| slot.takes_tile()).count() as u8;
+                        if used > real + extra {
+                            continue;
+                        }
+                        let mut moves = moves;
+                        moves[color] = mv;
+                        self.moves(state, color + 1, moves, gain + (real - board) as u32, found);
+                    }
+                }
+            }
+        }
+    }
+
+    fn melds(&self, start: State) -> Vec<TileSet> {
+        let mut melds = vec![];
+        let mut runs = [[TileSet::default(); 2]; COLORS];
+        let mut state = start;
+        while let Some(Some((_, moves))) = self.memo.get(&state) {
+            let mut leftovers: [Vec<Tile>; COLORS] = Default::default();
+            for (color, mv) in moves.iter().enumerate() {
+                let Some(tile) = self.tile(color, state.value) else {
+                    continue;
+                };
+                let mut tiles: Vec<_> = repeat_n(tile, mv.real as usize)
+                    .chain(repeat_n(Tile::Joker, mv.jokers as usize))
+                    .collect();
+                for (slot, run) in mv.slots.iter().zip(&mut runs[color]) {
+                    match slot {
+                        Slot::Idle => {}
+                        Slot::End => melds.push(std::mem::take(run)),
+                        Slot::Start | Slot::Extend => {
+                            if let Some(tile) = tiles.pop() {
+                                *run += tile;
+                            }
+                        }
+                    }
+                }
+                leftovers[color] = tiles;
+            }
+
+            for group in groups(moves)
+                .into_iter()
+                .flatten()
+                .filter(|&group| group != 0)
+            {
+                let tiles = (0..COLORS)
+                    .filter(|&color| group & 1 << color != 0)
+                    .filter_map(|color| leftovers[color].pop());
+                melds.push(tiles.collect());
+            }
+
+            let next = state.after(moves);
+            for (color, mv) in moves.iter().enumerate() {
+                let [a, b] = state.runs[color];
+                if mv.slots[0].apply(a) > mv.slots[1].apply(b) {
+                    runs[color].swap(0, 1);
+                }
+            }
+            state = next;
+        }
+        melds.extend(runs.iter().flatten().filter(|run| !run.is_empty()));
+        melds
+    }
+}
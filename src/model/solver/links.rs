@@ -0,0 +1,80 @@
+use std::hash::Hash;
+
+use super::{Solution, Solver};
+use crate::model::{Combos, PieceUniverse, Rummikub, Tile, TileSet};
+use crate::utils::exact_cover::{Column, DancingLinks};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Choice {
+    Meld(TileSet),
+    Keep(Tile),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Element {
+    Tile(Tile),
+    Kept,
+}
+
+// Only needs the melds of a universe, so it solves card rummy as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LinksSolver<U = Rummikub> {
+    universe: U,
+}
+
+impl<U: PieceUniverse> LinksSolver<U> {
+    pub fn new(universe: U) -> Self {
+        Self { universe }
+    }
+
+    fn first_cover(&self, board: TileSet, rack: TileSet, kept: usize) -> Option<Vec<TileSet>> {
+        let pool = board + rack;
+        let melds = self
+            .universe
+            .combos()
+            .all()
+            .filter(|&combo| combo <= pool)
+            .flat_map(|combo| {
+                let meld = Choice::Meld(combo);
+                combo
+                    .into_iter()
+                    .map(move |tile| (meld, Element::Tile(tile)))
+            });
+        let keeps = rack.unique_tiles().flat_map(|tile| {
+            [Element::Tile(tile), Element::Kept].map(|element| (Choice::Keep(tile), element))
+        });
+        let columns = pool
+            .unique_tiles()
+            .map(|tile| {
+                (
+                    Element::Tile(tile),
+                    Column::primary(pool.amount(tile) as usize),
+                )
+            })
+            .chain([(Element::Kept, Column::secondary(kept))]);
+        let mut dl = DancingLinks::<u32, _, _>::new(melds.chain(keeps), columns);
+
+        let choices = dl.solutions().next()?;
+        let melds = choices.into_iter().filter_map(|choice| match choice {
+            Choice::Meld(meld) => Some(meld),
+            Choice::Keep(_) => None,
+        });
+        Some(melds.collect())
+    }
+}
+
+impl<U: PieceUniverse> Solver for LinksSolver<U> {
+    fn combos(&self) -> &Combos {
+        self.universe.combos()
+    }
+
+    fn solve(&self, board: TileSet, rack: TileSet) -> Option<Solution> {
+        let pool = board + rack;
+        let combos = self.universe.combos();
+        let playable = rack.filter(|tile| combos.with(tile).any(|combo| combo <= pool));
+        // the fewer tiles are allowed to stay on the rack, the more get played
+        (0..=playable.len())
+            .find_map(|kept| self.first_cover(board, playable, kept))
+            .map(|melds| Solution::new(melds, board))
+    }
+}
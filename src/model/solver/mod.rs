@@ -0,0 +1,291 @@
+mod bitboard;
+mod constraints;
+mod disruption;
+mod dynamic;
+mod links;
+mod transposition;
+
+use super::{Board, BoardError, Combos, PieceUniverse, Rummikub, TileSet, Universe};
+
+pub use bitboard::BitboardSolver;
+pub use constraints::{Constraints, JokerSwap};
+pub use dynamic::DynamicSolver;
+pub use links::LinksSolver;
+pub use transposition::CacheStats;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Solution {
+    pub melds: Vec<TileSet>,
+    pub played: TileSet,
+    pub swaps: Vec<JokerSwap>,
+    pub cache: Option<CacheStats>,
+}
+
+impl Solution {
+    fn new(melds: Vec<TileSet>, board: TileSet) -> Self {
+        let played = melds
+            .iter()
+            .fold(TileSet::default(), |all, &meld| all + meld)
+            - board;
+        Self {
+            melds,
+            played,
+            swaps: vec![],
+            cache: None,
+        }
+    }
+
+    pub fn played_count(&self) -> usize {
+        self.played.len()
+    }
+
+    // Melds left as they were keep their place from `before`, so a joker at
+    // the end of a run still stands for what it did.
+    pub fn board(&self, before: &Board) -> Result<Board, BoardError> {
+        let mut unused = before.melds().to_vec();
+        let mut board = Board::default();
+        for &tiles in &self.melds {
+            match unused.iter().position(|meld| meld.tiles() == tiles) {
+                Some(i) => board.place(unused.remove(i))?,
+                None => board.add(tiles)?,
+            }
+        }
+        Ok(board)
+    }
+}
+
+pub trait Solver {
+    fn solve(&self, board: TileSet, rack: TileSet) -> Option<Solution>;
+
+    // the melds `solve` arranges, which the constrained searches grow and swap by
+    fn combos(&self) -> &Combos {
+        Rummikub.combos()
+    }
+
+    fn solve_with(
+        &self,
+        board: TileSet,
+        rack: TileSet,
+        constraints: &Constraints,
+    ) -> Option<Solution> {
+        constraints::solve_with(self, board, rack, constraints)
+    }
+
+    fn solve_turn(
+        &self,
+        board: &Board,
+        rack: TileSet,
+        rules: Rules,
+        constraints: &Constraints,
+    ) -> Option<Solution> {
+        constraints::solve_turn(self, board, rack, rules, constraints)
+    }
+
+    fn solve_gently(
+        &self,
+        board: &Board,
+        rack: TileSet,
+        rules: Rules,
+        constraints: &Constraints,
+    ) -> Option<Solution> {
+        disruption::solve_gently(self, board, rack, rules, constraints)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rules {
+    #[default]
+    Standard,
+    NoManipulation,
+}
+
+impl Rules {
+    pub fn all() -> impl Iterator<Item = Rules> {
+        [Rules::Standard, Rules::NoManipulation].into_iter()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rules::Standard => "Standard",
+            Rules::NoManipulation => "No manipulation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Backend {
+    #[default]
+    DancingLinks,
+    DynamicProgramming,
+    Bitboard,
+}
+
+impl Backend {
+    pub fn all() -> impl Iterator<Item = Backend> {
+        [
+            Backend::DancingLinks,
+            Backend::DynamicProgramming,
+            Backend::Bitboard,
+        ]
+        .into_iter()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::DancingLinks => "Dancing links",
+            Backend::DynamicProgramming => "Dynamic programming",
+            Backend::Bitboard => "Bitboard",
+        }
+    }
+
+    pub fn solver(self) -> Box<dyn Solver> {
+        match self {
+            Backend::DancingLinks => Box::new(LinksSolver::new(Rummikub)),
+            Backend::DynamicProgramming => Box::new(DynamicSolver),
+            Backend::Bitboard => Box::new(BitboardSolver),
+        }
+    }
+
+    // Only the links solver takes a universe's own melds, the others know
+    // Rummikub's and serve nothing else.
+    pub fn solver_for(self, universe: Universe) -> Option<Box<dyn Solver>> {
+        match (self, universe) {
+            (_, Universe::Rummikub) => Some(self.solver()),
+            (Backend::DancingLinks, _) => Some(Box::new(LinksSolver::new(universe.pieces()))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::model::{all_combos, is_combo, tiles, unseen, Color, Meld, Tile, Value};
+    use crate::utils::rng::Rng;
+
+    fn position(seed: u64, melds: usize, rack: usize) -> (TileSet, TileSet) {
+        let mut rng = Rng::seeded(seed);
+        let combos: Vec<_> = all_combos().collect();
+        let mut deck = unseen(TileSet::default(), TileSet::default()).unwrap();
+        let mut board = TileSet::default();
+        let mut laid = 0;
+        while laid < melds {
+            let combo = combos[rng.below(combos.len())];
+            if combo <= deck {
+                deck -= combo;
+                board += combo;
+                laid += 1;
+            }
+        }
+        let tiles: Vec<Tile> = deck.into_iter().collect();
+        (board, rng.sample(&tiles, rack).into_iter().collect())
+    }
+
+    fn check(solution: &Solution, board: TileSet, rack: TileSet) {
+        assert!(solution.melds.iter().all(|&meld| is_combo(meld)));
+        let all = solution
+            .melds
+            .iter()
+            .fold(TileSet::default(), |all, &meld| all + meld);
+        assert!(board <= all && all <= board + rack);
+        assert_eq!(all - board, solution.played);
+    }
+
+    #[test]
+    fn backends_agree() {
+        for seed in 0..20 {
+            let (board, rack) = position(seed, 3, 6);
+            let played: Vec<_> = Backend::all()
+                .map(|backend| {
+                    let solution = backend.solver().solve(board, rack).unwrap();
+                    check(&solution, board, rack);
+                    solution.played_count()
+                })
+                .collect();
+            assert!(
+                played.iter().all(|&count| count == played[0]),
+                "{board:?} {rack:?}: {played:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn solutions_lay_out_a_board() {
+        let low = Value::from_code(4).unwrap();
+        let run = Meld::run(Color::Red, low, tiles![R5, R6, J]).unwrap();
+        let mut before = Board::default();
+        before.place(run).unwrap();
+        let rack = tiles![B9, G9, U9];
+        for backend in Backend::all() {
+            let solution = backend.solver().solve(before.tiles(), rack).unwrap();
+            let board = solution.board(&before).unwrap();
+            assert_eq!(board.tiles(), before.tiles() + rack);
+            // the joker still stands for R4, not R7
+            assert!(board.melds().contains(&run));
+        }
+    }
+
+    #[test]
+    fn special_jokers_need_the_joker_edition() {
+        let board = tiles![R3, R4, R5, M];
+        for backend in Backend::all() {
+            assert!(backend.solver().solve(board, tiles![R6]).is_none());
+        }
+
+        let board = tiles![R3, R4, M, R4, R3];
+        let rack = tiles![R5, B5, G5];
+        for backend in Backend::all() {
+            assert!(backend.solver().solve(board, rack).is_none());
+            let Some(solver) = backend.solver_for(Universe::JokerEdition) else {
+                assert_ne!(backend, Backend::DancingLinks);
+                continue;
+            };
+            assert_eq!(solver.solve(board, rack).unwrap().played, rack);
+        }
+    }
+
+    #[test]
+    fn only_dancing_links_serves_other_universes() {
+        // Q-K-A only makes a run of cards
+        let rack = tiles![R12, R13, R1];
+        for backend in Backend::all() {
+            match backend.solver_for(Universe::Cards) {
+                Some(solver) => {
+                    let solution = solver.solve(TileSet::default(), rack).unwrap();
+                    assert_eq!(solution.played, rack);
+                }
+                None => assert_ne!(backend, Backend::DancingLinks),
+            }
+        }
+    }
+
+    #[test]
+    fn unarrangeable_board() {
+        let board = [Tile::Joker].into_iter().collect();
+        for backend in Backend::all() {
+            assert_eq!(backend.solver().solve(board, TileSet::default()), None);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn benchmark() {
+        for seed in 0..10 {
+            let (board, rack) = position(seed, 6, 10);
+            for backend in Backend::all() {
+                let start = Instant::now();
+                let played = backend
+                    .solver()
+                    .solve(board, rack)
+                    .map(|s| s.played_count());
+                println!(
+                    "{seed} {}: {played:?} in {:?}",
+                    backend.name(),
+                    start.elapsed()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+
+use crate::model::{Color, TileSet};
+
+const MAX_ENTRIES: usize = 1 << 17;
+
+lazy_static! {
+    static ref COLOR_PERMUTATIONS: Vec<[u64; 4]> = (0..256u64)
+        .map(|n| [n & 3, n >> 2 & 3, n >> 4 & 3, n >> 6 & 3])
+        .filter(|to| to.iter().fold(0, |seen, &color| seen | 1 << color) == 0b1111)
+        .collect();
+}
+
+// Rummikub's melds treat the colours alike, so positions that only differ by
+// a colour permutation share a key. With no run left to make only groups
+// remain, and those never mix values, so the values can be reordered as well.
+// Ace-high runs or special jokers break both, which keeps the table to the
+// bitboard solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    board: u128,
+    rack: u128,
+    groups_only: bool,
+}
+
+impl Key {
+    pub fn new(board: TileSet, rack: TileSet) -> Self {
+        let (board, rack) = COLOR_PERMUTATIONS
+            .iter()
+            .map(|&to| (board.permute_colors(to), rack.permute_colors(to)))
+            .min_by_key(|(board, rack)| (board.bits(), rack.bits()))
+            .unwrap_or((board, rack));
+        let groups_only = !runs_possible(board + rack);
+        let (board, rack) = if groups_only {
+            let mut order: Vec<u64> = (0..13).collect();
+            order.sort_by_key(|&value| {
+                Reverse((board.value_column(value), rack.value_column(value)))
+            });
+            let mut to = [0; 13];
+            for (place, &value) in order.iter().enumerate() {
+                to[value as usize] = place as u64;
+            }
+            (board.permute_values(to), rack.permute_values(to))
+        } else {
+            (board, rack)
+        };
+        Self {
+            board: board.bits(),
+            rack: rack.bits(),
+            groups_only,
+        }
+    }
+}
+
+fn runs_possible(pool: TileSet) -> bool {
+    let jokers = pool.jokers();
+    Color::all().any(|color| {
+        let values = pool.values_of(color);
+        match jokers {
+            0 => values & values >> 1 & values >> 2 != 0,
+            1 => values & (values >> 1 | values >> 2) != 0,
+            _ => values != 0,
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+    pub evictions: usize,
+}
+
+impl CacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        self.hits as f64 / (self.hits + self.misses).max(1) as f64
+    }
+}
+
+// Once full the table starts over rather than growing past MAX_ENTRIES.
+#[derive(Debug, Default)]
+pub struct Table {
+    entries: HashMap<Key, Option<usize>>,
+    stats: CacheStats,
+}
+
+impl Table {
+    pub fn get(&mut self, key: &Key) -> Option<Option<usize>> {
+        let found = self.entries.get(key).copied();
+        if found.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        found
+    }
+
+    pub fn insert(&mut self, key: Key, value: Option<usize>) {
+        if self.entries.len() >= MAX_ENTRIES {
+            self.stats.evictions += self.entries.len();
+            self.entries.clear();
+        }
+        self.entries.insert(key, value);
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            ..self.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{meld, tiles};
+
+    #[test]
+    fn colors_are_interchangeable() {
+        let board = meld!(R3..=R5);
+        let rack = tiles![U6, G6];
+        let swapped = |set: TileSet| set.permute_colors([3, 2, 1, 0]);
+        assert_ne!(board, swapped(board));
+        assert_eq!(
+            Key::new(board, rack),
+            Key::new(swapped(board), swapped(rack))
+        );
+        assert_ne!(Key::new(board, rack), Key::new(board, tiles![U7]));
+    }
+
+    #[test]
+    fn values_shift_without_runs() {
+        assert_eq!(
+            Key::new(meld!(R2, U2, B2), meld!(R9, U9, B9)),
+            Key::new(meld!(R11, U11, B11), meld!(R5, U5, B5))
+        );
+        // adjacent values could make runs, so they are kept apart
+        let twos = meld!(R2, U2, B2);
+        assert_ne!(
+            Key::new(twos, meld!(R3, U3, B3) + meld!(R4, U4, B4)),
+            Key::new(twos, meld!(R7, U7, B7) + meld!(R12, U12, B12))
+        );
+    }
+}
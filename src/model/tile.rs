@@ -1,5 +1,6 @@
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+#[derive(
+    Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default, Serialize, Deserialize,
+)]
 pub enum Color {
     #[default]
     Red,
@@ -10,6 +11,8 @@ pub enum Color {
 
 use std::fmt::{Debug, Display};
 
+use serde::{Deserialize, Serialize};
+
 use Color::*;
 
 impl Color {
@@ -22,7 +25,7 @@ impl Color {
         }
     }
 
-    fn code(self) -> u64 {
+    const fn code(self) -> u64 {
         match self {
             Red => 0,
             Green => 1,
@@ -46,7 +49,7 @@ impl Color {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 pub struct Value(u8);
 
 impl Value {
@@ -54,11 +57,11 @@ impl Value {
         (1..=13).map(Value)
     }
 
-    pub fn value(self) -> u8 {
+    pub const fn value(self) -> u8 {
         self.0
     }
 
-    pub fn from_code(code: u64) -> Option<Self> {
+    pub const fn from_code(code: u64) -> Option<Self> {
         if code < 1 || code > 13 {
             return None;
         }
@@ -78,35 +81,91 @@ impl Display for Value {
     }
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+// Jokers from newer editions that keep their own placement rules instead of
+// standing in for any tile. There is a single one of each.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum Special {
+    // the middle of a run laid out and then back again, R3 R4 M R4 R3
+    Mirror,
+    // the missing value where a run carries on in another colour, R3 R4 C U6 U7
+    ColorChange,
+    // a third copy of a tile laid twice, B9 B9 D
+    Duplicate,
+}
+
+impl Special {
+    pub fn name(self) -> &'static str {
+        match self {
+            Special::Mirror => "mirror",
+            Special::ColorChange => "color change",
+            Special::Duplicate => "duplicate",
+        }
+    }
+
+    const fn code(self) -> u64 {
+        match self {
+            Special::Mirror => 0,
+            Special::ColorChange => 1,
+            Special::Duplicate => 2,
+        }
+    }
+
+    fn from_code(code: u64) -> Option<Self> {
+        Some(match code {
+            0 => Special::Mirror,
+            1 => Special::ColorChange,
+            2 => Special::Duplicate,
+            _ => return None,
+        })
+    }
+
+    pub fn all() -> impl Iterator<Item = Special> {
+        [Special::Mirror, Special::ColorChange, Special::Duplicate].into_iter()
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum Tile {
     Normal { color: Color, value: Value },
     Joker,
+    Special(Special),
 }
 
 impl Tile {
-    const JOKER_CODE: u64 = 52;
-    // pub const SIZE: usize = 53;
+    pub const JOKER_CODE: u64 = 52;
+    const JOKER_POINTS: u32 = 30;
+    pub const SIZE: usize = 56;
 
-    pub fn code(self) -> u64 {
+    pub const fn code(self) -> u64 {
         match self {
             Tile::Normal { color, value } => {
                 let color = color.code();
-                let value = value.value() as u64;
+                let value = value.value() as u64 - 1;
                 value * 4 + color
             }
             Tile::Joker => Self::JOKER_CODE,
+            Tile::Special(special) => Self::JOKER_CODE + 1 + special.code(),
         }
     }
     pub fn from_code(code: u64) -> Option<Self> {
         if code == Self::JOKER_CODE {
             return Some(Self::Joker);
         }
+        if code > Self::JOKER_CODE {
+            return Special::from_code(code - Self::JOKER_CODE - 1).map(Self::Special);
+        }
         let color = Color::from_code(code % 4)?;
-        let value = Value::from_code(code / 4)?;
+        let value = Value::from_code(code / 4 + 1)?;
         Some(Self::Normal { color, value })
     }
 
+    pub fn points(self) -> u32 {
+        match self {
+            Tile::Normal { value, .. } => value.value() as u32,
+            Tile::Joker | Tile::Special(_) => Self::JOKER_POINTS,
+        }
+    }
+
     #[allow(unused)]
     pub fn all() -> impl Iterator<Item = Tile> {
         Color::all()
@@ -129,6 +188,30 @@ impl Debug for Tile {
                 write!(f, "{letter}{value}")
             }
             Tile::Joker => write!(f, "J"),
+            Tile::Special(Special::Mirror) => write!(f, "M"),
+            Tile::Special(Special::ColorChange) => write!(f, "C"),
+            Tile::Special(Special::Duplicate) => write!(f, "D"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        let codes: Vec<_> = Tile::all().map(Tile::code).collect();
+        assert_eq!(codes.len(), 53);
+        for (tile, code) in Tile::all().zip(&codes) {
+            assert_eq!(Tile::from_code(*code), Some(tile));
         }
+        // R13 used to share its code with the joker
+        let king = Tile::Normal {
+            color: Red,
+            value: Value(13),
+        };
+        assert_ne!(king.code(), Tile::JOKER_CODE);
+        assert_eq!(codes.iter().filter(|&&code| code == king.code()).count(), 1);
     }
-}
\ No newline at end of file
+}
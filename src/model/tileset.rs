@@ -1,10 +1,33 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 use std::{cmp::Ordering, iter::from_fn};
 
-use super::Tile;
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Default)]
+use super::{Color, Tile, Value};
+
+// one bit per value for a single colour, values four bits apart
+const COLOR_LANE: u64 = 0x1_1111_1111_1111;
+const VALUE_COLUMN: u64 = 0xF;
+// the joker and the special jokers after it, which no permutation moves
+const JOKER_BITS: u64 = !0 << Tile::JOKER_CODE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TileSetError {
+    TooManyCopies(Tile),
+    NotPresent(Tile),
+}
+
+impl Display for TileSetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TileSetError::TooManyCopies(tile) => write!(f, "There are only two {tile:?}"),
+            TileSetError::NotPresent(tile) => write!(f, "There is no {tile:?} to take"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct TileSet {
     once: u64,
     twice: u64,
@@ -27,7 +50,37 @@ impl TileSet {
         2
     }
 
-    pub fn add(self, tile: Tile) -> Self {
+    pub fn try_add(self, tile: Tile) -> Result<Self, TileSetError> {
+        match self.amount(tile) {
+            2 => Err(TileSetError::TooManyCopies(tile)),
+            _ => Ok(self.saturating_add(tile)),
+        }
+    }
+
+    pub fn try_remove(self, tile: Tile) -> Result<Self, TileSetError> {
+        match self.amount(tile) {
+            0 => Err(TileSetError::NotPresent(tile)),
+            _ => Ok(self.saturating_remove(tile)),
+        }
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Result<Self, TileSetError> {
+        let third = self.twice & rhs.once | self.once & rhs.twice;
+        match Tile::from_code(third.trailing_zeros() as u64) {
+            Some(tile) => Err(TileSetError::TooManyCopies(tile)),
+            None => Ok(self.saturating_add_all(rhs)),
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, TileSetError> {
+        match rhs.saturating_sub(self).into_iter().next() {
+            Some(tile) => Err(TileSetError::NotPresent(tile)),
+            None => Ok(self.saturating_sub(rhs)),
+        }
+    }
+
+    // a third copy is dropped
+    pub fn saturating_add(self, tile: Tile) -> Self {
         let code_bit = 1 << tile.code();
         if self.once & code_bit == 0 {
             Self {
@@ -42,7 +95,8 @@ impl TileSet {
         }
     }
 
-    pub fn remove(self, tile: Tile) -> Self {
+    // removing an absent tile changes nothing
+    pub fn saturating_remove(self, tile: Tile) -> Self {
         let code_bit = 1 << tile.code();
         if self.twice & code_bit != 0 {
             Self {
@@ -56,6 +110,83 @@ impl TileSet {
             }
         }
     }
+
+    pub fn saturating_add_all(self, rhs: Self) -> Self {
+        let once = self.once | rhs.once;
+        let twice = self.twice | rhs.twice | (self.once & rhs.once);
+        Self { once, twice }
+    }
+
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        let twice = self.twice & (!rhs.once);
+        let once = (self.once & (!rhs.once)) | (self.twice & (!rhs.twice));
+        Self { once, twice }
+    }
+
+    pub fn len(self) -> usize {
+        (self.once.count_ones() + self.twice.count_ones()) as usize
+    }
+
+    pub fn jokers(self) -> u8 {
+        self.amount(Tile::Joker)
+    }
+
+    // copies of the special jokers, which only some universes play
+    pub fn specials(self) -> Self {
+        self.filter(|tile| matches!(tile, Tile::Special(_)))
+    }
+
+    pub fn points(self) -> u32 {
+        self.into_iter().map(Tile::points).sum()
+    }
+
+    // the smaller number of copies of every tile
+    pub fn intersection(self, rhs: Self) -> Self {
+        Self {
+            once: self.once & rhs.once,
+            twice: self.twice & rhs.twice,
+        }
+    }
+
+    // the copies only one of the two has
+    pub fn symmetric_difference(self, rhs: Self) -> Self {
+        (self - rhs) + (rhs - self)
+    }
+
+    pub fn of_color(self, color: Color) -> Self {
+        self.masked(COLOR_LANE << color as u64)
+    }
+
+    pub fn of_value(self, value: Value) -> Self {
+        self.masked(VALUE_COLUMN << (4 * (value.value() as u64 - 1)))
+    }
+
+    // tiles of every colour, in colour code order
+    pub fn color_histogram(self) -> [u8; 4] {
+        std::array::from_fn(|color| self.masked(COLOR_LANE << color).len() as u8)
+    }
+
+    // tiles of every value, value `v` at index `v - 1`
+    pub fn value_histogram(self) -> [u8; 13] {
+        std::array::from_fn(|value| self.masked(VALUE_COLUMN << (4 * value)).len() as u8)
+    }
+
+    // every copy of the tiles `keep` accepts
+    pub fn filter(self, keep: impl Fn(Tile) -> bool) -> Self {
+        let mask = self
+            .unique_tiles()
+            .filter(|&tile| keep(tile))
+            .fold(0, |mask, tile| mask | 1 << tile.code());
+        self.masked(mask)
+    }
+
+    fn masked(self, mask: u64) -> Self {
+        Self {
+            once: self.once & mask,
+            twice: self.twice & mask,
+        }
+    }
+
     pub fn unique_tiles(self) -> impl Iterator<Item = Tile> {
         let mut bitmap = self.once;
         from_fn(move || {
@@ -69,12 +200,60 @@ impl TileSet {
     pub fn is_empty(self) -> bool {
         self.once == 0 && self.twice == 0
     }
+
+    pub fn bits(self) -> u128 {
+        (self.twice as u128) << 64 | self.once as u128
+    }
+
+    // bit `v - 1` is set when the colour has value `v`
+    pub fn values_of(self, color: Color) -> u16 {
+        let start = Tile::Normal {
+            color,
+            value: Value::default(),
+        }
+        .code();
+        let lane = self.once >> start & COLOR_LANE;
+        (0..13).fold(0, |values, value| {
+            values | ((lane >> (4 * value) & 1) as u16) << value
+        })
+    }
+
+    // moves colour code `c` to `to[c]`, `to` has to be a permutation
+    pub fn permute_colors(self, to: [u64; 4]) -> Self {
+        let map = |mask: u64| {
+            (0..4).fold(mask & JOKER_BITS, |moved, color| {
+                moved | (mask >> color & COLOR_LANE) << to[color as usize]
+            })
+        };
+        Self {
+            once: map(self.once),
+            twice: map(self.twice),
+        }
+    }
+
+    // copies of every colour at the value with index `value`, once bits low
+    pub fn value_column(self, value: u64) -> u8 {
+        let column = |mask: u64| (mask >> (4 * value) & VALUE_COLUMN) as u8;
+        column(self.once) | column(self.twice) << 4
+    }
+
+    // moves the value with index `v` to `to[v]`, `to` has to be a permutation
+    pub fn permute_values(self, to: [u64; 13]) -> Self {
+        let map = |mask: u64| {
+            (0..13).fold(mask & JOKER_BITS, |moved, value| {
+                moved | (mask >> (4 * value) & VALUE_COLUMN) << (4 * to[value as usize])
+            })
+        };
+        Self {
+            once: map(self.once),
+            twice: map(self.twice),
+        }
+    }
 }
 
 impl FromIterator<Tile> for TileSet {
     fn from_iter<T: IntoIterator<Item = Tile>>(iter: T) -> Self {
-        iter.into_iter()
-            .fold(Self::default(), |set, tile| set.add(tile))
+        iter.into_iter().fold(Self::default(), Self::saturating_add)
     }
 }
 
@@ -96,13 +275,151 @@ impl PartialOrd for TileSet {
     }
 }
 
+// Sub-multisets pick a number of copies of every tile: a subset of the tiles
+// present once, and for those a subset of the ones present twice.
+impl TileSet {
+    pub fn sub_multisets(self) -> SubMultisets {
+        SubMultisets {
+            set: self,
+            next: Some(Self::default()),
+        }
+    }
+
+    pub fn sub_multisets_of_len(self, len: usize) -> SubMultisetsOfLen {
+        let tiles: Vec<_> = self
+            .unique_tiles()
+            .map(|tile| (tile, self.amount(tile)))
+            .collect();
+        let mut counts = vec![0; tiles.len()];
+        let done = fill(&tiles, &mut counts, 0, len) > 0;
+        SubMultisetsOfLen {
+            tiles,
+            counts,
+            done,
+        }
+    }
+
+    pub fn sub_multisets_with(self, tile: Tile) -> impl Iterator<Item = TileSet> {
+        let rest = self.try_remove(tile).ok();
+        rest.into_iter()
+            .flat_map(move |rest| rest.sub_multisets().map(move |sub| sub + tile))
+    }
+
+    // how many sub-multisets of `len` tiles there are
+    pub fn count_sub_multisets(self, len: usize) -> u64 {
+        let mut ways = vec![0u64; len + 1];
+        ways[0] = 1;
+        for tile in self.unique_tiles() {
+            let copies = self.amount(tile) as usize;
+            for total in (1..=len).rev() {
+                ways[total] += (1..=copies.min(total))
+                    .map(|taken| ways[total - taken])
+                    .sum::<u64>();
+            }
+        }
+        ways[len]
+    }
+
+    // how many ways there are to pick `sub` out of the copies in this set
+    pub fn ways_to_pick(self, sub: TileSet) -> u64 {
+        sub.unique_tiles()
+            .map(|tile| binomial(self.amount(tile), sub.amount(tile)))
+            .product()
+    }
+}
+
+fn binomial(n: u8, k: u8) -> u64 {
+    if k > n {
+        return 0;
+    }
+    (0..k as u64).fold(1, |ways, i| ways * (n as u64 - i) / (i + 1))
+}
+
+pub struct SubMultisets {
+    set: TileSet,
+    next: Option<TileSet>,
+}
+
+impl Iterator for SubMultisets {
+    type Item = TileSet;
+
+    fn next(&mut self) -> Option<TileSet> {
+        let current = self.next?;
+        // count through the second copies first, then through the first ones
+        let doubled = current.once & self.set.twice;
+        let twice = current.twice.wrapping_sub(doubled) & doubled;
+        self.next = if twice != 0 {
+            Some(TileSet { twice, ..current })
+        } else {
+            let once = current.once.wrapping_sub(self.set.once) & self.set.once;
+            (once != 0).then_some(TileSet { once, twice: 0 })
+        };
+        Some(current)
+    }
+}
+
+// Counts of every unique tile, most copies of the lowest tiles first.
+pub struct SubMultisetsOfLen {
+    tiles: Vec<(Tile, u8)>,
+    counts: Vec<u8>,
+    done: bool,
+}
+
+// puts `len` copies into `counts[from..]` as early as they fit, returning how
+// many didn't
+fn fill(tiles: &[(Tile, u8)], counts: &mut [u8], from: usize, mut len: usize) -> usize {
+    for (count, &(_, copies)) in counts[from..].iter_mut().zip(&tiles[from..]) {
+        *count = (copies as usize).min(len) as u8;
+        len -= *count as usize;
+    }
+    len
+}
+
+impl Iterator for SubMultisetsOfLen {
+    type Item = TileSet;
+
+    fn next(&mut self) -> Option<TileSet> {
+        if self.done {
+            return None;
+        }
+        let current = self
+            .tiles
+            .iter()
+            .zip(&self.counts)
+            .flat_map(|(&(tile, _), &count)| std::iter::repeat_n(tile, count as usize))
+            .collect();
+
+        // move one copy from the last tile that can give one to the tiles after it
+        let mut after = 0;
+        let mut room = 0;
+        self.done = true;
+        for at in (0..self.counts.len()).rev() {
+            if self.counts[at] > 0 && room > after {
+                self.counts[at] -= 1;
+                fill(&self.tiles, &mut self.counts, at + 1, after + 1);
+                self.done = false;
+                break;
+            }
+            after += self.counts[at] as usize;
+            room += self.tiles[at].1 as usize;
+        }
+        Some(current)
+    }
+}
+
+// The operators saturate like the methods they call: they are for sets that
+// can't overflow, such as the parts of one pool.
 impl Add for TileSet {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let once = self.once | rhs.once;
-        let twice = self.twice | rhs.twice | (self.once & rhs.once);
-        Self { once, twice }
+        self.saturating_add_all(rhs)
+    }
+}
+
+impl AddAssign for TileSet {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
     }
 }
 
@@ -110,9 +427,7 @@ impl Sub for TileSet {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self {
-        let twice = self.twice & (!rhs.once);
-        let once = (self.once & (!rhs.once)) | (self.twice & (!rhs.twice));
-        Self { once, twice }
+        self.saturating_sub(rhs)
     }
 }
 
@@ -126,13 +441,13 @@ impl Add<Tile> for TileSet {
     type Output = Self;
 
     fn add(self, rhs: Tile) -> Self::Output {
-        self.add(rhs)
+        self.saturating_add(rhs)
     }
 }
 
 impl AddAssign<Tile> for TileSet {
     fn add_assign(&mut self, rhs: Tile) {
-        *self = self.add(rhs);
+        *self = self.saturating_add(rhs);
     }
 }
 
@@ -140,13 +455,13 @@ impl Sub<Tile> for TileSet {
     type Output = Self;
 
     fn sub(self, rhs: Tile) -> Self::Output {
-        self.remove(rhs)
+        self.saturating_remove(rhs)
     }
 }
 
 impl SubAssign<Tile> for TileSet {
     fn sub_assign(&mut self, rhs: Tile) {
-        *self = self.remove(rhs);
+        *self = self.saturating_remove(rhs);
     }
 }
 
@@ -168,13 +483,10 @@ impl IntoIterator for TileSet {
 
 pub struct Tiles(TileSet);
 
-impl Iterator for Tiles {
-    type Item = Tile;
-
-    fn next(&mut self) -> Option<Tile> {
-        let tz = self.0.once.trailing_zeros() as u64;
-        let tile = Tile::from_code(tz)?;
-        let bit = 1 << tz;
+impl Tiles {
+    fn take(&mut self, code: u64) -> Option<Tile> {
+        let tile = Tile::from_code(code)?;
+        let bit = 1 << code;
         if (self.0.twice & bit) != 0 {
             self.0.twice ^= bit;
         } else {
@@ -183,3 +495,108 @@ impl Iterator for Tiles {
         Some(tile)
     }
 }
+
+impl Iterator for Tiles {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        self.take(self.0.once.trailing_zeros() as u64)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl DoubleEndedIterator for Tiles {
+    fn next_back(&mut self) -> Option<Tile> {
+        self.take(self.0.once.checked_ilog2()? as u64)
+    }
+}
+
+impl ExactSizeIterator for Tiles {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{tile, tiles};
+
+    #[test]
+    fn checked_arithmetic() {
+        let five = tile!(R5);
+        let pair = tiles![R5, R5];
+        assert_eq!(pair.try_add(five), Err(TileSetError::TooManyCopies(five)));
+        assert_eq!(pair.saturating_add(five), pair);
+        assert_eq!(
+            TileSet::default().try_remove(five),
+            Err(TileSetError::NotPresent(five))
+        );
+        assert_eq!(pair.try_remove(five), Ok(tiles![R5]));
+
+        let six = tiles![U6];
+        assert_eq!(
+            (pair + six).checked_sub(six + six),
+            Err(TileSetError::NotPresent(tile!(U6)))
+        );
+        assert_eq!((pair + six).checked_sub(six), Ok(pair));
+        assert_eq!(
+            pair.checked_add(tiles![R5]),
+            Err(TileSetError::TooManyCopies(five))
+        );
+        assert_eq!(six.checked_add(six), Ok(tiles![U6, U6]));
+    }
+
+    #[test]
+    fn queries() {
+        let tiles = tiles![R5, R5, R6, U5, J];
+        assert_eq!(tiles.len(), 5);
+        assert_eq!(tiles.jokers(), 1);
+        assert_eq!(tiles.points(), 21 + 30);
+        assert_eq!(tiles.of_color(Color::Red).len(), 3);
+        assert_eq!(tiles.of_value(Value::from_code(5).unwrap()).len(), 3);
+        assert_eq!(tiles.color_histogram(), [3, 0, 0, 1]);
+        assert_eq!(tiles.value_histogram()[4..6], [3, 1]);
+        assert_eq!(tiles.filter(|tile| tile != Tile::Joker).len(), 4);
+
+        let other = tiles![R5, G1];
+        assert_eq!(tiles.intersection(other), tiles![R5]);
+        assert_eq!(tiles.symmetric_difference(other), tiles![R5, R6, U5, J, G1]);
+    }
+
+    #[test]
+    fn iterates_from_both_ends() {
+        let mut iter = tiles![R1, U9, J].into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some(tile!(J)));
+        assert_eq!(iter.next(), Some(tile!(R1)));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.rev().collect::<Vec<_>>(), vec![tile!(U9)]);
+    }
+
+    #[test]
+    fn sub_multisets() {
+        use std::collections::HashSet;
+        let tiles = tiles![R1, R1, G4, U13, J, J];
+        let all: Vec<_> = tiles.sub_multisets().collect();
+        let unique: HashSet<_> = all.iter().copied().collect();
+        assert_eq!(all.len(), 3 * 2 * 2 * 3);
+        assert_eq!(unique.len(), all.len());
+        assert!(all.iter().all(|&sub| sub <= tiles));
+
+        for len in 0..=tiles.len() + 1 {
+            let sized: HashSet<_> = tiles.sub_multisets_of_len(len).collect();
+            let expected: HashSet<_> = all.iter().copied().filter(|sub| sub.len() == len).collect();
+            assert_eq!(sized, expected, "{len}");
+            assert_eq!(tiles.count_sub_multisets(len), expected.len() as u64);
+        }
+
+        let with: Vec<_> = tiles.sub_multisets_with(tile!(R1)).collect();
+        assert_eq!(with.len(), 2 * 2 * 2 * 3);
+        assert!(with.iter().all(|sub| sub.cointains(tile!(R1))));
+        assert_eq!(tiles.sub_multisets_with(tile!(R2)).count(), 0);
+
+        let pair = tiles![R1, J];
+        assert_eq!(tiles.ways_to_pick(pair), 4);
+        assert_eq!(pair.ways_to_pick(tiles), 0);
+    }
+}
@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use super::{Board, BoardError, Tile, TileSet, TileSetError};
+
+pub const TURN_TIME: Duration = Duration::from_secs(60);
+pub const PENALTY_DRAWS: usize = 3;
+
+// Where the time comes from, so tests can move it along by hand.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrowserClock;
+
+impl Clock for BrowserClock {
+    fn now(&self) -> Duration {
+        Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Penalty {
+    TimeUp,
+    Failed(BoardError),
+    // board tiles that were picked up and never laid again
+    LeftOff(TileSet),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    Played(TileSet),
+    Drew(TileSet),
+    Penalized(Penalty, TileSet),
+}
+
+// A turn works on a copy of the board and rack. Running out of time, a failed
+// manipulation or tiles left off the board put both back the way the turn
+// found them, and the player draws PENALTY_DRAWS tiles instead.
+#[derive(Debug, Clone)]
+pub struct Turn<C: Clock> {
+    clock: C,
+    deadline: Duration,
+    start: (Board, TileSet),
+    board: Board,
+    rack: TileSet,
+    failed: Option<BoardError>,
+}
+
+impl<C: Clock> Turn<C> {
+    pub fn start(clock: C, limit: Duration, board: Board, rack: TileSet) -> Self {
+        Self {
+            deadline: clock.now() + limit,
+            clock,
+            start: (board.clone(), rack),
+            board,
+            rack,
+            failed: None,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn rack(&self) -> TileSet {
+        self.rack
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_sub(self.clock.now())
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    // Tiles picked up from the board that still have to be laid again. Tiles
+    // that came from neither the board nor the rack are an error.
+    pub fn loose(&self) -> Result<TileSet, TileSetError> {
+        let (board, rack) = &self.start;
+        (board.tiles() + *rack)
+            .checked_sub(self.rack)?
+            .checked_sub(self.board.tiles())
+    }
+
+    // rearranges the board, the first failure costs the turn
+    pub fn edit<T>(
+        &mut self,
+        edit: impl FnOnce(&mut Board) -> Result<T, BoardError>,
+    ) -> Result<T, BoardError> {
+        let result = edit(&mut self.board);
+        if let Err(error) = result {
+            self.failed.get_or_insert(error);
+        }
+        result
+    }
+
+    // Lays a meld out of loose tiles first and then the rack. Tiles the player
+    // doesn't have fail the turn like a bad manipulation.
+    pub fn lay(&mut self, meld: TileSet) -> Result<(), BoardError> {
+        let rack = self
+            .loose()
+            .and_then(|loose| self.rack.checked_sub(meld - meld.intersection(loose)));
+        self.rack = self.edit(|board| {
+            let rack = rack?;
+            board.add(meld)?;
+            Ok(rack)
+        })?;
+        Ok(())
+    }
+
+    pub fn end(self, pile: &mut Vec<Tile>) -> (Board, TileSet, Ending) {
+        let penalty = if self.is_over() {
+            Some(Penalty::TimeUp)
+        } else if let Some(error) = self.failed {
+            Some(Penalty::Failed(error))
+        } else {
+            match self.loose() {
+                Err(error) => Some(Penalty::Failed(error.into())),
+                Ok(loose) if !loose.is_empty() => Some(Penalty::LeftOff(loose)),
+                Ok(_) => None,
+            }
+        };
+        let (board, rack) = self.start;
+        if let Some(penalty) = penalty {
+            let drawn = draw(pile, PENALTY_DRAWS);
+            return (board, rack + drawn, Ending::Penalized(penalty, drawn));
+        }
+        if self.rack == rack {
+            let drawn = draw(pile, 1);
+            return (board, rack + drawn, Ending::Drew(drawn));
+        }
+        (self.board, self.rack, Ending::Played(rack - self.rack))
+    }
+}
+
+fn draw(pile: &mut Vec<Tile>, amount: usize) -> TileSet {
+    (0..amount).filter_map(|_| pile.pop()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::model::{meld, tile, tiles, Color};
+
+    // shared by the test and the turn it moves along
+    #[derive(Debug, Default, Clone)]
+    struct ManualClock(Rc<Cell<Duration>>);
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            self.0.set(self.0.get() + by);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Duration {
+            self.0.get()
+        }
+    }
+
+    fn position() -> (Board, TileSet, Vec<Tile>) {
+        let board = Board::from_melds([meld!(R3..=R6)]).unwrap();
+        (
+            board,
+            tiles![R7, G7, B7, U2],
+            vec![tile!(G1), tile!(G2), tile!(G3), tile!(G4)],
+        )
+    }
+
+    #[test]
+    fn plays_within_the_time() {
+        let clock = ManualClock::default();
+        let (board, rack, mut pile) = position();
+        let mut turn = Turn::start(clock.clone(), TURN_TIME, board, rack);
+        clock.advance(Duration::from_secs(20));
+        assert_eq!(turn.remaining(), Duration::from_secs(40));
+
+        // the run is picked up and laid again with R7 on the end
+        turn.edit(|board| board.remove(0)).unwrap();
+        assert_eq!(turn.loose(), Ok(meld!(R3..=R6)));
+        turn.lay(meld!(R3..=R7)).unwrap();
+        let (board, rack, ending) = turn.end(&mut pile);
+        assert_eq!(ending, Ending::Played(tiles![R7]));
+        assert_eq!(board.tiles(), meld!(R3..=R7));
+        assert_eq!(rack, tiles![G7, B7, U2]);
+        assert_eq!(pile.len(), 4);
+    }
+
+    #[test]
+    fn time_up_rolls_back_and_draws_three() {
+        let clock = ManualClock::default();
+        let (start, rack, mut pile) = position();
+        let mut turn = Turn::start(clock.clone(), TURN_TIME, start.clone(), rack);
+        turn.lay(tiles![R7, G7, B7]).unwrap();
+        clock.advance(TURN_TIME);
+        assert!(turn.is_over());
+
+        let (board, after, ending) = turn.end(&mut pile);
+        assert_eq!(board, start);
+        assert_eq!(after, rack + tiles![G2, G3, G4]);
+        assert_eq!(
+            ending,
+            Ending::Penalized(Penalty::TimeUp, tiles![G2, G3, G4])
+        );
+    }
+
+    #[test]
+    fn failed_manipulations_are_penalized() {
+        let clock = ManualClock::default();
+        let (start, rack, mut pile) = position();
+        let mut turn = Turn::start(clock.clone(), TURN_TIME, start.clone(), rack);
+        let error = turn
+            .edit(|board| board.extend_group(0, Color::Blue))
+            .unwrap_err();
+        turn.lay(tiles![R7, G7, B7]).unwrap();
+        let (board, _, ending) = turn.end(&mut pile);
+        assert_eq!(board, start);
+        assert!(matches!(ending, Ending::Penalized(Penalty::Failed(failed), _) if failed == error));
+
+        // picking up a meld without laying it again counts too
+        let mut turn = Turn::start(clock.clone(), TURN_TIME, start.clone(), rack);
+        turn.edit(|board| board.remove(0)).unwrap();
+        let (board, _, ending) = turn.end(&mut pile);
+        assert_eq!(board, start);
+        assert!(
+            matches!(ending, Ending::Penalized(Penalty::LeftOff(left), _) if left == meld!(R3..=R6))
+        );
+    }
+
+    #[test]
+    fn tiles_from_nowhere_fail_the_turn() {
+        let clock = ManualClock::default();
+        let (start, rack, mut pile) = position();
+        let mut turn = Turn::start(clock.clone(), TURN_TIME, start.clone(), rack);
+        turn.edit(|board| board.add(meld!(B1..=B3))).unwrap();
+        assert_eq!(turn.loose(), Err(TileSetError::NotPresent(tile!(B1))));
+        let (board, _, ending) = turn.end(&mut pile);
+        assert_eq!(board, start);
+        assert!(matches!(ending, Ending::Penalized(Penalty::Failed(_), _)));
+    }
+
+    #[test]
+    fn laying_missing_tiles_fails_the_turn() {
+        let clock = ManualClock::default();
+        let (start, rack, mut pile) = position();
+        let mut turn = Turn::start(clock.clone(), TURN_TIME, start.clone(), rack);
+        let missing = TileSetError::NotPresent(tile!(U7));
+        assert_eq!(turn.lay(tiles![R7, B7, U7]), Err(missing.into()));
+        assert_eq!(turn.rack(), rack);
+        assert_eq!(turn.board(), &start);
+
+        // laying what the rack does hold afterwards doesn't save the turn
+        turn.lay(tiles![R7, G7, B7]).unwrap();
+        let (board, after, ending) = turn.end(&mut pile);
+        assert_eq!((board, after), (start, rack + tiles![G2, G3, G4]));
+        assert_eq!(
+            ending,
+            Ending::Penalized(Penalty::Failed(missing.into()), tiles![G2, G3, G4])
+        );
+    }
+
+    #[test]
+    fn draws_one_without_a_play() {
+        let (start, rack, mut pile) = position();
+        let turn = Turn::start(ManualClock::default(), TURN_TIME, start, rack);
+        let (_, after, ending) = turn.end(&mut pile);
+        assert_eq!(ending, Ending::Drew(tiles![G4]));
+        assert_eq!(after, rack + tile!(G4));
+    }
+}
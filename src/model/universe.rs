@@ -0,0 +1,280 @@
+use lazy_static::lazy_static;
+
+use super::{Combos, Special, Tile, TileSet};
+
+// What a game plays with and which melds it allows. Pieces share Tile's codes,
+// four suits of thirteen ranks and a joker, and TileSet holds at most two
+// copies of each, so a universe can use the one or two decks that fit.
+pub trait PieceUniverse {
+    fn name(&self) -> &'static str;
+
+    // copies of every numbered piece
+    fn copies(&self) -> u8;
+
+    fn jokers(&self) -> u8;
+
+    // whether a run may end on the lowest rank, as Q-K-A
+    fn ace_high(&self) -> bool {
+        false
+    }
+
+    // most jokers a meld of `len` pieces may hold
+    fn jokers_in_meld(&self, len: usize) -> usize;
+
+    // one of each, played only the way its own rules allow
+    fn special_jokers(&self) -> &[Special] {
+        &[]
+    }
+
+    fn combos(&self) -> &Combos;
+
+    fn deck(&self) -> TileSet {
+        let numbered: TileSet = (0..self.copies())
+            .flat_map(|_| Tile::all())
+            .filter(|&tile| tile != Tile::Joker)
+            .chain(
+                Special::all()
+                    .filter(|special| self.special_jokers().contains(special))
+                    .map(Tile::Special),
+            )
+            .collect();
+        (0..self.jokers()).fold(numbered, |deck, _| deck + Tile::Joker)
+    }
+}
+
+impl<U: PieceUniverse + ?Sized> PieceUniverse for &U {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn copies(&self) -> u8 {
+        (**self).copies()
+    }
+
+    fn jokers(&self) -> u8 {
+        (**self).jokers()
+    }
+
+    fn ace_high(&self) -> bool {
+        (**self).ace_high()
+    }
+
+    fn jokers_in_meld(&self, len: usize) -> usize {
+        (**self).jokers_in_meld(len)
+    }
+
+    fn special_jokers(&self) -> &[Special] {
+        (**self).special_jokers()
+    }
+
+    fn combos(&self) -> &Combos {
+        (**self).combos()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rummikub;
+
+impl PieceUniverse for Rummikub {
+    fn name(&self) -> &'static str {
+        "Rummikub"
+    }
+
+    fn copies(&self) -> u8 {
+        2
+    }
+
+    fn jokers(&self) -> u8 {
+        2
+    }
+
+    fn jokers_in_meld(&self, _: usize) -> usize {
+        2
+    }
+
+    fn combos(&self) -> &Combos {
+        super::combinations::rummikub()
+    }
+}
+
+// Rummikub with the mirror, colour change and duplicate jokers of newer editions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JokerEdition;
+
+impl PieceUniverse for JokerEdition {
+    fn name(&self) -> &'static str {
+        "Joker edition"
+    }
+
+    fn copies(&self) -> u8 {
+        2
+    }
+
+    fn jokers(&self) -> u8 {
+        2
+    }
+
+    fn jokers_in_meld(&self, _: usize) -> usize {
+        2
+    }
+
+    fn special_jokers(&self) -> &[Special] {
+        &[Special::Mirror, Special::ColorChange, Special::Duplicate]
+    }
+
+    fn combos(&self) -> &Combos {
+        super::combinations::joker_edition()
+    }
+}
+
+// A 52-card deck, or two shuffled together, with two jokers. Aces count low,
+// or high too when `ace_high`, and a meld needs more natural cards than jokers.
+#[derive(Debug, Clone)]
+pub struct Cards {
+    decks: u8,
+    ace_high: bool,
+    combos: Combos,
+}
+
+impl Cards {
+    // TileSet holds two copies of a card at most, so two decks is the limit
+    pub fn new(decks: u8, ace_high: bool) -> Option<Self> {
+        if !(1..=2).contains(&decks) {
+            return None;
+        }
+        let mut cards = Self {
+            decks,
+            ace_high,
+            combos: Combos::default(),
+        };
+        cards.combos = Combos::generate(&cards);
+        Some(cards)
+    }
+}
+
+impl PieceUniverse for Cards {
+    fn name(&self) -> &'static str {
+        "Cards"
+    }
+
+    fn copies(&self) -> u8 {
+        self.decks
+    }
+
+    fn jokers(&self) -> u8 {
+        2
+    }
+
+    fn ace_high(&self) -> bool {
+        self.ace_high
+    }
+
+    fn jokers_in_meld(&self, len: usize) -> usize {
+        (len - 1) / 2
+    }
+
+    fn combos(&self) -> &Combos {
+        &self.combos
+    }
+}
+
+lazy_static! {
+    // two decks with aces high, the way rummy is mostly played
+    static ref CARDS: Cards = Cards::new(2, true).unwrap();
+}
+
+// The universes a game can be set up with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Universe {
+    #[default]
+    Rummikub,
+    JokerEdition,
+    Cards,
+}
+
+impl Universe {
+    pub fn all() -> impl Iterator<Item = Universe> {
+        [Universe::Rummikub, Universe::JokerEdition, Universe::Cards].into_iter()
+    }
+
+    pub fn pieces(self) -> &'static dyn PieceUniverse {
+        match self {
+            Universe::Rummikub => &Rummikub,
+            Universe::JokerEdition => &JokerEdition,
+            Universe::Cards => &*CARDS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::solver::LinksSolver;
+    use crate::model::{meld, tiles, Solver};
+
+    #[test]
+    fn rummikub_keeps_its_melds() {
+        assert_eq!(Rummikub.combos().all().count(), 6301);
+        assert_eq!(Rummikub.deck().len(), 106);
+        assert!(Rummikub.combos().contains(tiles![R5, J, J]));
+        assert!(!Rummikub.combos().contains(tiles![R12, R13, R1]));
+    }
+
+    #[test]
+    fn special_jokers_follow_their_own_rules() {
+        let edition = JokerEdition.combos();
+        assert_eq!(JokerEdition.deck().len(), 109);
+        assert_eq!(JokerEdition.deck().specials(), tiles![M, C, D]);
+        assert!(Rummikub.deck().specials().is_empty());
+        assert!(edition.all().count() > Rummikub.combos().all().count());
+        assert!(edition.contains(meld!(R5..=R9)));
+
+        assert!(edition.contains(tiles![R3, R4, M, R4, R3]));
+        assert!(!edition.contains(tiles![R3, M, R3]));
+        assert!(!edition.contains(tiles![R3, R4, M, R4, R5]));
+
+        assert!(edition.contains(tiles![R3, R4, C, U6, U7]));
+        assert!(!edition.contains(tiles![R3, R4, C, R6, R7]));
+        assert!(!edition.contains(tiles![R3, R4, C, U7, U8]));
+
+        assert!(edition.contains(tiles![B9, B9, D]));
+        assert!(!edition.contains(tiles![B9, G9, D]));
+        assert!(!edition.contains(tiles![B9, J, D]));
+        assert!(!Rummikub.combos().contains(tiles![B9, B9, D]));
+
+        let rack = tiles![R3, R4, M, R4, R3, C, D];
+        let solution = LinksSolver::new(JokerEdition).solve(tiles![G5, G5], rack);
+        assert_eq!(solution.unwrap().played, rack - tiles![C]);
+    }
+
+    #[test]
+    fn cards_count_aces_both_ways() {
+        let low = Cards::new(1, false).unwrap();
+        let high = Cards::new(1, true).unwrap();
+        assert_eq!(low.deck().len(), 54);
+        assert!(Cards::new(3, true).is_none());
+
+        let queen_king_ace = tiles![U12, U13, U1];
+        assert!(!low.combos().contains(queen_king_ace));
+        assert!(high.combos().contains(queen_king_ace));
+        assert!(high.combos().contains(meld!(U1..=U3)));
+        assert!(!high.combos().contains(tiles![U13, U1, U2]));
+
+        // jokers can't outnumber the natural cards
+        assert!(high.combos().contains(tiles![B7, G7, J]));
+        assert!(!high.combos().contains(tiles![B7, J, J]));
+        assert!(high.combos().contains(tiles![B7, B8, J, J, B11]));
+    }
+
+    #[test]
+    fn solves_card_hands() {
+        let hand = tiles![U12, U13, U1, G7, B7, R7, J];
+        let cards = LinksSolver::new(Cards::new(1, true).unwrap());
+        let solution = cards.solve(TileSet::default(), hand).unwrap();
+        assert_eq!(solution.played, hand);
+
+        // a run can't turn the corner from the king in Rummikub
+        let rummikub = LinksSolver::new(Rummikub).solve(TileSet::default(), hand);
+        assert_eq!(rummikub.unwrap().played, hand - tiles![U1]);
+    }
+}
@@ -0,0 +1,639 @@
+use crate::utils::hkt::{Dimension, First as Sets, Second as Elements, TypeConstructor};
+use std::hash::Hash;
+
+use derivative::Derivative;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Column {
+    need: usize,
+    limit: usize,
+}
+
+impl Column {
+    pub fn primary(times: usize) -> Self {
+        Self::between(times, times)
+    }
+
+    pub fn secondary(times: usize) -> Self {
+        Self::between(0, times)
+    }
+
+    pub fn between(need: usize, limit: usize) -> Self {
+        Self {
+            need,
+            limit: limit.max(need),
+        }
+    }
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self::primary(1)
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct Header<E, I> {
+    value: E,
+    first: I,
+    amount: usize,
+}
+
+impl<'a, I: 'a> TypeConstructor<'a> for Header<(), I> {
+    type Out<T: 'a> = Header<T, I>;
+}
+
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Default(bound = ""))]
+pub struct DancingLinks<I: Addressable, S, E> {
+    sets: Vec<Header<S, I>>,
+    elements: Vec<Header<E, I>>,
+    columns: Vec<Column>,
+    covered: Vec<usize>,
+    cells: Vec<Cell<I>>,
+    backstack: Vec<I>,
+}
+
+impl<I: Addressable, S, E> DancingLinks<I, S, E> {
+    pub fn new(
+        xs: impl IntoIterator<Item = (S, E)>,
+        columns: impl IntoIterator<Item = (E, Column)>,
+    ) -> Self
+    where
+        S: Clone + Hash + Eq,
+        E: Clone + Hash + Eq,
+    {
+        let mut dl = Self::default();
+        let mut builder = builder::DancingLinksBuilder::new(&mut dl);
+
+        for (elem, column) in columns {
+            builder.add_column(elem, column);
+        }
+        for (set, elem) in xs {
+            builder.add_link(set, elem);
+        }
+        builder.finish();
+
+        dl
+    }
+
+    pub fn solutions(&mut self) -> Solutions<'_, I, S, E> {
+        Solutions {
+            dl: self,
+            frames: vec![],
+            started: false,
+        }
+    }
+
+    fn cell(&self, i: I) -> Option<&Cell<I>> {
+        i.address().map(|i| &self.cells[i])
+    }
+
+    fn cell_mut(&mut self, i: I) -> Option<&mut Cell<I>> {
+        i.address().map(move |i| &mut self.cells[i])
+    }
+
+    fn first_mut<D: Dimension>(&mut self, d: D, owner: I) -> Option<&mut I> {
+        type HeaderHK<'b, I> = (&'b mut (), Vec<()>, Header<(), I>);
+        let headers = d.val::<HeaderHK<I>, _, _>(&mut self.sets, &mut self.elements);
+        owner.address().map(move |j| &mut headers[j].first)
+    }
+
+    fn walk(&self, first: I, d: impl Dimension) -> Vec<(I, Cell<I>)> {
+        let mut cells = vec![];
+        let mut i = first;
+        while let Some(&cell) = self.cell(i) {
+            cells.push((i, cell));
+            i = cell.next(d);
+        }
+        cells
+    }
+
+    fn set_cells(&self, set: I) -> Vec<(I, Cell<I>)> {
+        set.address()
+            .map_or_else(Vec::new, |s| self.walk(self.sets[s].first, Sets))
+    }
+
+    fn element_cells(&self, element: usize) -> Vec<(I, Cell<I>)> {
+        self.walk(self.elements[element].first, Elements)
+    }
+
+    fn remove(&mut self, i: I, d: impl Dimension) {
+        let Some(&cur) = self.cell(i) else { return };
+        if let Some(prev) = self.cell_mut(cur.prev(d)) {
+            *prev.next_mut(d) = cur.next(d);
+        } else if let Some(first) = self.first_mut(d, cur.owner(d)) {
+            *first = cur.next(d);
+        }
+        if let Some(next) = self.cell_mut(cur.next(d)) {
+            *next.prev_mut(d) = cur.prev(d);
+        }
+    }
+
+    fn restore(&mut self, i: I, d: impl Dimension) {
+        let Some(&cur) = self.cell(i) else { return };
+        if let Some(prev) = self.cell_mut(cur.prev(d)) {
+            *prev.next_mut(d) = i;
+        } else if let Some(first) = self.first_mut(d, cur.owner(d)) {
+            *first = i;
+        }
+        if let Some(next) = self.cell_mut(cur.next(d)) {
+            *next.prev_mut(d) = i;
+        }
+    }
+
+    fn hide(&mut self, set: I) {
+        for (i, cell) in self.set_cells(set) {
+            self.remove(i, Elements);
+            if let Some(e) = cell.element.address() {
+                self.elements[e].amount -= 1;
+            }
+        }
+        self.backstack.push(set);
+    }
+
+    fn unhide_to(&mut self, mark: usize) {
+        while self.backstack.len() > mark {
+            let Some(set) = self.backstack.pop() else {
+                return;
+            };
+            for (i, cell) in self.set_cells(set).into_iter().rev() {
+                self.restore(i, Elements);
+                if let Some(e) = cell.element.address() {
+                    self.elements[e].amount += 1;
+                }
+            }
+        }
+    }
+
+    fn left(&self, element: usize) -> usize {
+        self.columns[element].limit - self.covered[element]
+    }
+
+    fn needs(&self, element: usize) -> bool {
+        self.covered[element] < self.columns[element].need
+    }
+
+    fn hide_overflowing(&mut self, element: usize) {
+        let left = self.left(element);
+        for (_, cell) in self.element_cells(element) {
+            if cell.times > left {
+                self.hide(cell.set);
+            }
+        }
+    }
+
+    fn select(&mut self, set: I) {
+        let cells = self.set_cells(set);
+        for (_, cell) in &cells {
+            if let Some(e) = cell.element.address() {
+                self.covered[e] += cell.times;
+            }
+        }
+        for (_, cell) in &cells {
+            if let Some(e) = cell.element.address() {
+                self.hide_overflowing(e);
+            }
+        }
+    }
+
+    fn unselect(&mut self, set: I, mark: usize) {
+        self.unhide_to(mark);
+        for (_, cell) in self.set_cells(set) {
+            if let Some(e) = cell.element.address() {
+                self.covered[e] -= cell.times;
+            }
+        }
+    }
+
+    fn first_set(&self, element: usize) -> Option<I> {
+        self.cell(self.elements[element].first).map(|cell| cell.set)
+    }
+
+    fn choose_element(&self) -> Option<usize> {
+        (0..self.elements.len())
+            .filter(|&e| self.needs(e))
+            .min_by_key(|&e| self.elements[e].amount)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame<I> {
+    element: usize,
+    mark: usize,
+    trying: Option<(I, usize)>,
+}
+
+pub struct Solutions<'a, I: Addressable, S, E> {
+    dl: &'a mut DancingLinks<I, S, E>,
+    frames: Vec<Frame<I>>,
+    started: bool,
+}
+
+impl<'a, I: Addressable, S: Clone, E> Solutions<'a, I, S, E> {
+    fn descend(&mut self) -> bool {
+        let Some(element) = self.dl.choose_element() else {
+            return true;
+        };
+        self.frames.push(Frame {
+            element,
+            mark: self.dl.backstack.len(),
+            trying: None,
+        });
+        false
+    }
+
+    fn solution(&self) -> Vec<S> {
+        self.frames
+            .iter()
+            .filter_map(|frame| frame.trying)
+            .filter_map(|(set, _)| set.address())
+            .map(|s| self.dl.sets[s].value.clone())
+            .collect()
+    }
+}
+
+impl<'a, I: Addressable, S: Clone, E> Iterator for Solutions<'a, I, S, E> {
+    type Item = Vec<S>;
+
+    fn next(&mut self) -> Option<Vec<S>> {
+        if !self.started {
+            self.started = true;
+            if self.descend() {
+                return Some(vec![]);
+            }
+        }
+        loop {
+            let frame = self.frames.last_mut()?;
+            if let Some((set, mark)) = frame.trying.take() {
+                self.dl.unselect(set, mark);
+                // solutions with this set are done, the rest must avoid it
+                self.dl.hide(set);
+            }
+            let frame = *self.frames.last()?;
+            let Some(set) = self.dl.first_set(frame.element) else {
+                self.frames.pop();
+                self.dl.unhide_to(frame.mark);
+                continue;
+            };
+            let mark = self.dl.backstack.len();
+            self.dl.select(set);
+            if let Some(frame) = self.frames.last_mut() {
+                frame.trying = Some((set, mark));
+            }
+            if self.descend() {
+                return Some(self.solution());
+            }
+        }
+    }
+}
+
+impl<'a, I: Addressable, S, E> Drop for Solutions<'a, I, S, E> {
+    fn drop(&mut self) {
+        while let Some(frame) = self.frames.pop() {
+            if let Some((set, mark)) = frame.trying {
+                self.dl.unselect(set, mark);
+            }
+            self.dl.unhide_to(frame.mark);
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+struct Cell<I: Addressable> {
+    prev_element: I,
+    next_element: I,
+    prev_set: I,
+    next_set: I,
+    set: I,
+    element: I,
+    times: usize,
+}
+
+impl<I: Addressable> Cell<I> {
+    fn prev(&self, d: impl Dimension) -> I {
+        d.of_same(self.prev_set, self.prev_element)
+    }
+
+    fn prev_mut(&mut self, d: impl Dimension) -> &mut I {
+        d.of_same(&mut self.prev_set, &mut self.prev_element)
+    }
+
+    fn next(&self, d: impl Dimension) -> I {
+        d.of_same(self.next_set, self.next_element)
+    }
+
+    fn next_mut(&mut self, d: impl Dimension) -> &mut I {
+        d.of_same(&mut self.next_set, &mut self.next_element)
+    }
+
+    fn owner(&self, d: impl Dimension) -> I {
+        d.of_same(self.set, self.element)
+    }
+}
+
+pub trait Addressable: Copy + Eq + TryInto<usize> + TryFrom<usize> + 'static {
+    const NULL: Self;
+    fn address(self) -> Option<usize> {
+        if self == Self::NULL {
+            None
+        } else {
+            self.try_into().ok()
+        }
+    }
+
+    fn from_address(address: usize) -> Self {
+        address.try_into().ok().unwrap_or(Self::NULL)
+    }
+}
+
+macro_rules! impl_addressable {
+    ($($t:ty),*) => {
+        $(
+            impl Addressable for $t {
+                const NULL: $t = !0;
+            }
+        )*
+    };
+}
+
+impl_addressable!(u8, u16, u32, u64, usize);
+
+mod builder {
+    use std::{collections::HashMap, hash::Hash};
+
+    use crate::utils::hkt::{At, First};
+
+    use super::*;
+
+    #[derive(Debug)]
+    pub(super) struct DancingLinksBuilder<'a, I: Addressable, S, E> {
+        dl: &'a mut DancingLinks<I, S, E>,
+        elem_map: HashMap<E, usize>,
+        set_map: HashMap<S, usize>,
+        link_map: HashMap<(usize, usize), usize>,
+    }
+
+    impl<'a, I: Addressable, S: Eq + Hash + Clone, E: Eq + Hash + Clone>
+        DancingLinksBuilder<'a, I, S, E>
+    {
+        pub(super) fn new(dl: &'a mut DancingLinks<I, S, E>) -> Self {
+            Self {
+                dl,
+                elem_map: HashMap::new(),
+                set_map: HashMap::new(),
+                link_map: HashMap::new(),
+            }
+        }
+
+        fn index<'b, D: Dimension>(&'b mut self, d: D, x: D::Out<'b, S, E>) -> usize
+        where
+            D::Out<'b, S, E>: Clone + Hash + Eq,
+        {
+            type HeaderHK<'b, I> = (&'b mut (), Vec<()>, Header<(), I>);
+            let headers = d.val::<HeaderHK<'b, I>, _, _>(&mut self.dl.sets, &mut self.dl.elements);
+
+            type MapHK<'b> = (&'b mut (), At<HashMap<(), usize>, First>);
+            let map = d.val::<MapHK, _, _>(&mut self.set_map, &mut self.elem_map);
+
+            *map.entry(x.clone()).or_insert_with(|| {
+                let i = headers.len();
+                headers.push(Header {
+                    value: x,
+                    first: I::NULL,
+                    amount: 0,
+                });
+                i
+            })
+        }
+
+        fn push_front<D: Dimension>(&mut self, d: D, j: usize, new: I) -> I {
+            type HeaderHK<'b, I> = (&'b mut (), Vec<()>, Header<(), I>);
+            let headers = d.val::<HeaderHK<I>, _, _>(&mut self.dl.sets, &mut self.dl.elements);
+
+            let old = headers[j].first;
+            headers[j].first = new;
+            headers[j].amount += 1;
+            if let Some(next) = self.dl.cell_mut(old) {
+                *next.prev_mut(d) = new;
+            }
+            old
+        }
+
+        pub(super) fn add_column(&mut self, elem: E, column: Column) {
+            let element = self.index(Elements, elem);
+            self.dl
+                .columns
+                .resize(self.dl.elements.len(), Column::default());
+            self.dl.columns[element] = column;
+        }
+
+        pub(super) fn add_link(&mut self, set: S, elem: E) {
+            let set = self.index(Sets, set);
+            let element = self.index(Elements, elem);
+            if let Some(&cell) = self.link_map.get(&(set, element)) {
+                self.dl.cells[cell].times += 1;
+                return;
+            }
+
+            let i = I::from_address(self.dl.cells.len());
+            self.link_map.insert((set, element), self.dl.cells.len());
+            self.dl.cells.push(Cell {
+                prev_element: I::NULL,
+                next_element: I::NULL,
+                prev_set: I::NULL,
+                next_set: I::NULL,
+                set: I::from_address(set),
+                element: I::from_address(element),
+                times: 1,
+            });
+            let next_set = self.push_front(Sets, set, i);
+            let next_element = self.push_front(Elements, element, i);
+            if let Some(cell) = self.dl.cell_mut(i) {
+                cell.next_set = next_set;
+                cell.next_element = next_element;
+            }
+        }
+
+        pub(super) fn finish(self) {
+            let dl = self.dl;
+            dl.columns.resize(dl.elements.len(), Column::default());
+            dl.covered = vec![0; dl.elements.len()];
+            let sets = (0..dl.sets.len()).map(I::from_address);
+            let unfit: Vec<_> = sets
+                .filter(|&set| {
+                    dl.set_cells(set).into_iter().any(|(_, cell)| {
+                        let limit = cell.element.address().map_or(0, |e| dl.columns[e].limit);
+                        cell.times > limit
+                    })
+                })
+                .collect();
+            for set in unfit {
+                dl.hide(set);
+            }
+            dl.backstack.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn sorted<S: Ord>(solutions: impl Iterator<Item = Vec<S>>) -> Vec<Vec<S>> {
+        let mut solutions: Vec<_> = solutions
+            .map(|mut solution| {
+                solution.sort();
+                solution
+            })
+            .collect();
+        solutions.sort();
+        solutions
+    }
+
+    #[test]
+    fn multiplicities_and_optional_columns() {
+        let sets = [
+            ("A", "a"),
+            ("AB", "a"),
+            ("AB", "b"),
+            ("AC", "a"),
+            ("AC", "c"),
+        ];
+        let links = sets.into_iter().chain([("BC", "b"), ("BC", "c")]);
+        let columns = [
+            ("a", Column::primary(2)),
+            ("b", Column::primary(1)),
+            ("c", Column::secondary(1)),
+        ];
+        let mut dl = DancingLinks::<u8, _, _>::new(links, columns);
+
+        assert_eq!(
+            sorted(dl.solutions()),
+            vec![vec!["A", "A", "BC"], vec!["A", "AB"], vec!["AB", "AC"]]
+        );
+    }
+
+    #[test]
+    fn full_columns_exclude_sets() {
+        let links = [("A", "a"), ("B", "a"), ("B", "z")];
+        let mut dl = DancingLinks::<u8, _, _>::new(links, [("z", Column::primary(0))]);
+
+        assert_eq!(sorted(dl.solutions()), vec![vec!["A"]]);
+    }
+
+    #[test]
+    fn dropped_iterator_restores_links() {
+        let links = [(1, 'a'), (2, 'a'), (3, 'b'), (4, 'b')];
+        let mut dl = DancingLinks::<u8, _, _>::new(links, []);
+
+        assert_eq!(dl.solutions().take(1).count(), 1);
+        assert_eq!(dl.solutions().count(), 4);
+    }
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn sudoku() {
+        let givens: Vec<_> = PUZZLE.chars().map(|c| c.to_digit(10)).collect();
+        let links = (0..81u32).flat_map(|cell| {
+            let (row, col) = (cell / 9, cell % 9);
+            let block = row / 3 * 3 + col / 3;
+            let digits = match givens[cell as usize] {
+                Some(digit) => digit..=digit,
+                None => 1..=9,
+            };
+            digits.flat_map(move |digit| {
+                [
+                    (0, cell),
+                    (1, row * 9 + digit),
+                    (2, col * 9 + digit),
+                    (3, block * 9 + digit),
+                ]
+                .map(|element| ((cell, digit), element))
+            })
+        });
+        let mut dl = DancingLinks::<u16, _, _>::new(links, []);
+
+        let solutions: Vec<_> = dl.solutions().collect();
+        assert_eq!(solutions.len(), 1);
+        let mut grid = solutions[0].clone();
+        grid.sort();
+        let grid: String = grid.iter().map(|&(_, digit)| digit.to_string()).collect();
+        assert_eq!(grid, SOLUTION);
+    }
+
+    const PENTOMINOES: [&[&str]; 12] = [
+        &[".XX", "XX.", ".X."],
+        &["XXXXX"],
+        &["XXXX", "X..."],
+        &["XX..", ".XXX"],
+        &["XX", "XX", "X."],
+        &["XXX", ".X.", ".X."],
+        &["X.X", "XXX"],
+        &["X..", "X..", "XXX"],
+        &["X..", "XX.", ".XX"],
+        &[".X.", "XXX", ".X."],
+        &["XXXX", ".X.."],
+        &["XX.", ".X.", ".XX"],
+    ];
+
+    fn orientations(shape: &[&str]) -> HashSet<Vec<(i32, i32)>> {
+        let cells: Vec<_> = shape
+            .iter()
+            .enumerate()
+            .flat_map(|(r, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == 'X')
+                    .map(move |(c, _)| (r as i32, c as i32))
+            })
+            .collect();
+        let mut found = HashSet::new();
+        let mut current = cells;
+        for _ in 0..4 {
+            current = current.iter().map(|&(r, c)| (c, -r)).collect();
+            for cells in [
+                current.clone(),
+                current.iter().map(|&(r, c)| (r, -c)).collect(),
+            ] {
+                let top = cells.iter().map(|&(r, _)| r).min().unwrap_or(0);
+                let left = cells.iter().map(|&(_, c)| c).min().unwrap_or(0);
+                let mut cells: Vec<_> = cells.iter().map(|&(r, c)| (r - top, c - left)).collect();
+                cells.sort();
+                found.insert(cells);
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn pentominoes_on_3x20() {
+        let (height, width) = (3, 20);
+        let mut links = vec![];
+        for (piece, shape) in PENTOMINOES.iter().enumerate() {
+            for cells in orientations(shape) {
+                for (dr, dc) in (0..height).flat_map(|r| (0..width).map(move |c| (r, c))) {
+                    let placed: Vec<_> = cells.iter().map(|&(r, c)| (r + dr, c + dc)).collect();
+                    if placed.iter().any(|&(r, c)| r >= height || c >= width) {
+                        continue;
+                    }
+                    let set = (piece, placed.clone());
+                    links.push((set.clone(), (0, piece as i32)));
+                    for (r, c) in placed {
+                        links.push((set.clone(), (1, r * width + c)));
+                    }
+                }
+            }
+        }
+        let mut dl = DancingLinks::<u32, _, _>::new(links, []);
+
+        // two tilings, each in its four mirror images
+        assert_eq!(dl.solutions().count(), 8);
+    }
+}
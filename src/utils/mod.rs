@@ -1,5 +1,7 @@
 pub mod vec;
 pub mod refs;
 pub mod hkt;
+pub mod exact_cover;
+pub mod rng;
 
 
@@ -0,0 +1,31 @@
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn sample<T: Copy>(&mut self, xs: &[T], amount: usize) -> Vec<T> {
+        let mut xs = xs.to_vec();
+        let amount = amount.min(xs.len());
+        for i in 0..amount {
+            let j = i + self.below(xs.len() - i);
+            xs.swap(i, j);
+        }
+        xs.truncate(amount);
+        xs
+    }
+}
@@ -0,0 +1,140 @@
+use yew::prelude::*;
+
+use super::TileLine;
+use crate::model::{self, Board, BoardError, Meld, TileSet};
+
+// edits a copy of the board and returns the new draft
+type Edit = Box<dyn Fn(&mut Board) -> Result<TileSet, BoardError>>;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub board: Board,
+    pub draft: TileSet,
+    pub on_board: Callback<Board>,
+    pub on_draft: Callback<TileSet>,
+}
+
+// A single drafted tile either takes a joker's place, handing the joker back,
+// or joins a group.
+fn place(board: &mut Board, index: usize, tile: model::Tile) -> Result<TileSet, BoardError> {
+    let meld = board.melds()[index];
+    if meld.stood_for().contains(&tile) && meld.tiles().cointains(model::Tile::Joker) {
+        board.swap_joker(index, tile)?;
+        return Ok(TileSet::default() + model::Tile::Joker);
+    }
+    match (meld, tile) {
+        (Meld::Group { .. }, model::Tile::Normal { color, .. }) => {
+            board.extend_group(index, color)?;
+            Ok(TileSet::default())
+        }
+        _ => Err(BoardError::NotStoodFor(tile)),
+    }
+}
+
+#[function_component]
+pub fn BoardEditor(props: &Props) -> Html {
+    let Props {
+        board,
+        draft,
+        on_board,
+        on_draft,
+    } = props.clone();
+    let error = use_state_eq(|| None::<BoardError>);
+
+    // both stay as they were when the edit fails
+    let edit = |change: Edit| {
+        let (board, on_board, on_draft, error) = (
+            board.clone(),
+            on_board.clone(),
+            on_draft.clone(),
+            error.clone(),
+        );
+        Callback::from(move |_| {
+            let mut changed = board.clone();
+            match change(&mut changed) {
+                Ok(drafted) => {
+                    on_board.emit(changed);
+                    on_draft.emit(drafted);
+                    error.set(None);
+                }
+                Err(cause) => error.set(Some(cause)),
+            }
+        })
+    };
+
+    let single = draft
+        .into_iter()
+        .next()
+        .filter(|_| draft.into_iter().nth(1).is_none());
+    let melds = board
+        .melds()
+        .iter()
+        .enumerate()
+        .map(|(index, &meld)| {
+            let split = match meld {
+                Meld::Run { .. } => {
+                    let (board, on_board, error) = (board.clone(), on_board.clone(), error.clone());
+                    Callback::from(move |tile: model::Tile| {
+                        let model::Tile::Normal { value, .. } = tile else {
+                            return;
+                        };
+                        let mut changed = board.clone();
+                        match changed.split_run(index, value) {
+                            Ok(()) => on_board.emit(changed),
+                            Err(cause) => error.set(Some(cause)),
+                        }
+                    })
+                }
+                Meld::Group { .. } => Callback::noop(),
+            };
+            let pick_up = edit(Box::new(move |board| {
+                let meld = board.remove(index)?;
+                Ok(draft.checked_add(meld.tiles())?)
+            }));
+            let merge = edit(Box::new(move |board| {
+                board.merge_runs(index, index + 1).map(|_| draft)
+            }));
+            let put = single.map(|tile| {
+                let onclick = edit(Box::new(move |board| {
+                    let rest = draft.try_remove(tile)?;
+                    Ok(rest.checked_add(place(board, index, tile)?)?)
+                }));
+                html! {
+                    <button class="button is-small" {onclick}>{ format!("Put {tile:?}") }</button>
+                }
+            });
+            let is_run = matches!(meld, Meld::Run { .. });
+            html! {
+                <div class="box">
+                    <TileLine tiles={meld.tiles()} on_remove={split} />
+                    <div class="buttons">
+                        <button class="button is-small" onclick={pick_up}>{ "Pick up" }</button>
+                        if is_run {
+                            <button class="button is-small" onclick={merge}>{ "Merge with next" }</button>
+                        }
+                        { put }
+                    </div>
+                </div>
+            }
+        })
+        .collect::<Html>();
+
+    let lay = edit(Box::new(move |board| {
+        board.add(draft).map(|_| TileSet::default())
+    }));
+    let remove = on_draft.reform(move |tile: model::Tile| draft.saturating_remove(tile));
+    let error = error.map(|cause| {
+        html! {
+            <div class="notification is-danger">{ cause.to_string() }</div>
+        }
+    });
+
+    html! {
+        <div class="container">
+            { melds }
+            <TileLine tiles={draft} on_remove={remove} />
+            <button class="button" onclick={lay}>{ "Lay meld" }</button>
+            { error }
+        </div>
+    }
+}
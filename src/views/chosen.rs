@@ -1,7 +1,7 @@
 use yew::prelude::*;
 
 use super::Tile;
-use crate::model::{self, TileSet};
+use crate::model::{self, Arrangement, Rack, TileSet};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
@@ -10,6 +10,10 @@ pub struct Props {
     pub on_remove: Callback<model::Tile>,
     #[prop_or_default]
     pub disabled: bool,
+    #[prop_or_default]
+    pub highlighted: TileSet,
+    #[prop_or_default]
+    pub sortable: bool,
 }
 
 #[function_component]
@@ -18,18 +22,63 @@ pub fn TileLine(props: &Props) -> Html {
         disabled,
         on_remove,
         tiles,
+        highlighted,
+        sortable,
     } = props;
-    let tiles = tiles.into_iter();
-    let tiles = tiles.map(|tile| {
+    let arrangement = use_state_eq(Arrangement::default);
+
+    // only as many copies as are highlighted get marked
+    let mut unmarked = *highlighted;
+    let mut button = |tile: model::Tile| {
         let on_click = on_remove.reform(move |_| tile.clone());
+        let highlighted = unmarked.cointains(tile);
+        unmarked = unmarked.saturating_remove(tile);
         html! {
-            <Tile {tile} {on_click} {disabled}/>
+            <Tile {tile} {on_click} {disabled} {highlighted}/>
         }
-    });
-    let tiles = tiles.collect::<Html>();
+    };
+
+    if !sortable {
+        let tiles = tiles.into_iter().map(button).collect::<Html>();
+        return html! {
+            <div class="buttons">
+                {tiles}
+            </div>
+        };
+    }
+
+    let switches = Arrangement::all()
+        .map(|option| {
+            let class = classes!(
+                "button",
+                "is-small",
+                (option == *arrangement).then_some("is-selected is-info")
+            );
+            let onclick = {
+                let arrangement = arrangement.clone();
+                Callback::from(move |_| arrangement.set(option))
+            };
+            html! {
+                <button {class} {onclick}>{ option.name() }</button>
+            }
+        })
+        .collect::<Html>();
+    let clusters = Rack::new(*tiles)
+        .arranged(*arrangement)
+        .into_iter()
+        .map(|cluster| {
+            let tiles = cluster.into_iter().map(&mut button).collect::<Html>();
+            html! {
+                <div class="buttons mr-4">
+                    {tiles}
+                </div>
+            }
+        })
+        .collect::<Html>();
     html! {
-        <div class="buttons">
-            {tiles}
+        <div>
+            <div class="buttons has-addons">{ switches }</div>
+            <div class="is-flex is-flex-wrap-wrap">{ clusters }</div>
         </div>
     }
 }
@@ -1,7 +1,7 @@
 use yew::prelude::*;
 
-use super::TileLine;
-use crate::model::{all_combos, TileSet};
+use super::{Tile, TileLine};
+use crate::model::{best_draw, near_melds, splits, PieceUniverse, Rummikub, TileSet};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct Props {
@@ -19,19 +19,60 @@ pub fn Combinations(props: &Props) -> Html {
     };
 
     let content = if *show {
-        let combinations = all_combos().filter(|ts| ts <= &tiles);
-        let combinations = combinations.map(|tiles| {
-            log::info!("{tiles:?}");
+        let combos = Rummikub.combos();
+        let splits = splits(combos, tiles).into_iter().map(|melds| {
+            let melds = melds.into_iter().map(|tiles| {
+                html! {
+                    <TileLine {tiles} />
+                }
+            });
             html! {
-                <TileLine {tiles} />
+                <div class="box">
+                    { melds.collect::<Html>() }
+                </div>
             }
         });
-        let combinations = combinations.collect::<Html>();
+        let splits = splits.collect::<Html>();
+
+        let near = near_melds(combos, tiles).into_iter().map(|near| {
+            html! {
+                <tr>
+                    <td><TileLine tiles={near.tiles} disabled=true /></td>
+                    <td><Tile tile={near.missing} disabled=true /></td>
+                </tr>
+            }
+        });
+        let near = near.collect::<Html>();
+
+        let best = match best_draw(combos, tiles) {
+            Some((tile, count)) => html! {
+                <div class="level">
+                    <div class="level-left">
+                        <div class="level-item">{ "Best draw" }</div>
+                        <div class="level-item"><Tile {tile} disabled=true /></div>
+                        <div class="level-item">{ format!("completes {count} melds") }</div>
+                    </div>
+                </div>
+            },
+            None => html! {},
+        };
 
         html! {
             <div>
                 <button class="button is-danger" {onclick}> {"Hide All Combinations"} </button>
-                { combinations }
+                <h2 class="title">{ "Ways to lay down" }</h2>
+                { splits }
+                <h2 class="title">{ "Almost melds" }</h2>
+                { best }
+                <table class="table is-striped">
+                <thead>
+                    <tr>
+                        <th>{ "Tiles" }</th>
+                        <th>{ "Missing" }</th>
+                    </tr>
+                </thead>
+                <tbody> { near } </tbody>
+                </table>
             </div>
         }
     } else {
@@ -0,0 +1,105 @@
+use gloo_timers::callback::Interval;
+use yew::prelude::*;
+
+use crate::model::{Board, BrowserClock, Ending, Penalty, TileSet, Turn, PENALTY_DRAWS, TURN_TIME};
+
+// milliseconds between looks at the clock
+const TICK: u32 = 250;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub board: Board,
+    pub rack: TileSet,
+    // the board and rack as the turn found them, once its time is up
+    #[prop_or_default]
+    pub on_time_up: Callback<(Board, TileSet)>,
+}
+
+#[function_component]
+pub fn Countdown(props: &Props) -> Html {
+    let Props {
+        board,
+        rack,
+        on_time_up,
+    } = props.clone();
+    let turn = use_state(|| None::<Turn<BrowserClock>>);
+    let timed_out = use_state_eq(|| false);
+    let redraw = use_force_update();
+
+    {
+        let (turn, timed_out) = (turn.clone(), timed_out.clone());
+        let running = turn.is_some();
+        use_effect_with_deps(
+            move |_| {
+                // the turn keeps the deadline, the interval only redraws until it passes
+                let interval = running.then(|| {
+                    Interval::new(TICK, move || match (*turn).clone() {
+                        Some(current) if current.is_over() => {
+                            let (board, rack, ending) = current.end(&mut vec![]);
+                            timed_out.set(matches!(ending, Ending::Penalized(Penalty::TimeUp, _)));
+                            on_time_up.emit((board, rack));
+                            turn.set(None);
+                        }
+                        _ => redraw.force_update(),
+                    })
+                });
+                move || drop(interval)
+            },
+            running,
+        );
+    }
+
+    let start = {
+        let (turn, timed_out) = (turn.clone(), timed_out.clone());
+        Callback::from(move |_| {
+            timed_out.set(false);
+            turn.set(Some(Turn::start(
+                BrowserClock,
+                TURN_TIME,
+                board.clone(),
+                rack,
+            )));
+        })
+    };
+
+    let shown = match &*turn {
+        Some(current) => {
+            let left = current.remaining().as_secs_f64().ceil();
+            let stop = {
+                let turn = turn.clone();
+                Callback::from(move |_| turn.set(None))
+            };
+            html! {
+                <>
+                    <progress class="progress is-info" value={left.to_string()} max={TURN_TIME.as_secs().to_string()} />
+                    <p>{ format!("{left} seconds left") }</p>
+                    <button class="button" onclick={stop}>{ "Done" }</button>
+                </>
+            }
+        }
+        None => {
+            let penalty = if *timed_out {
+                html! {
+                    <div class="notification is-warning">
+                        { format!("Time's up: the board is back as the turn found it, draw {PENALTY_DRAWS} tiles") }
+                    </div>
+                }
+            } else {
+                html! {}
+            };
+            html! {
+                <>
+                    { penalty }
+                    <button class="button is-primary" onclick={start}>{ "Start turn" }</button>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="container">
+            <h2 class="title">{ "Turn timer" }</h2>
+            { shown }
+        </div>
+    }
+}
@@ -1,8 +1,14 @@
-use crate::model::debug_info;
+use crate::model::{debug_info, CacheStats};
 use yew::prelude::*;
 
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    #[prop_or_default]
+    pub cache: Option<CacheStats>,
+}
+
 #[function_component]
-pub fn DebugInfo() -> Html {
+pub fn DebugInfo(props: &Props) -> Html {
     html! {
         <div class = "container">
 			<h2 class="title">{ "Debug info" }</h2>
@@ -14,7 +20,7 @@ pub fn DebugInfo() -> Html {
                 </tr>
             </thead>
             <tbody> {
-                debug_info().into_iter().map(|[name, v]|
+                debug_info(props.cache).into_iter().map(|[name, v]|
                     html! {
                         <tr>
                             <td>{ name }</td>
@@ -0,0 +1,59 @@
+use yew::prelude::*;
+
+use super::Tile;
+use crate::model::{draw_odds, play_probability, Board, TileSet};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub rack: TileSet,
+    #[prop_or_default]
+    pub board: Board,
+    #[prop_or(1)]
+    pub turns: usize,
+}
+
+#[function_component]
+pub fn DrawOdds(props: &Props) -> Html {
+    let Props { rack, board, turns } = props.clone();
+    let (chance, odds) = match (
+        play_probability(rack, &board, turns),
+        draw_odds(rack, &board, turns),
+    ) {
+        (Ok(chance), Ok(odds)) => (chance * 100.0, odds),
+        (Err(error), _) | (_, Err(error)) => {
+            return html! {
+                <div class="notification is-danger">{ error.to_string() }</div>
+            }
+        }
+    };
+
+    let rows = odds
+        .into_iter()
+        .filter(|odds| odds.enables_play)
+        .map(|odds| {
+            html! {
+                <tr>
+                    <td><Tile tile={odds.tile} disabled=true /></td>
+                    <td>{ odds.copies }</td>
+                    <td>{ format!("{:.1}%", odds.probability * 100.0) }</td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="container">
+            <h2 class="title">{ format!("Chance to complete a meld: {chance:.1}%") }</h2>
+            <table class="table is-striped">
+            <thead>
+                <tr>
+                    <th>{ "Tile" }</th>
+                    <th>{ "Unseen" }</th>
+                    <th>{ "Drawn" }</th>
+                </tr>
+            </thead>
+            <tbody> { rows } </tbody>
+            </table>
+        </div>
+    }
+}
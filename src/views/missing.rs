@@ -0,0 +1,82 @@
+use yew::prelude::*;
+
+use super::Tile;
+use crate::model::{missing_tiles, Missing, TileSet, TileSetError, Universe, MAX_MISSING};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub rack: TileSet,
+    pub board: TileSet,
+    // the deck the draws come from and the melds they make
+    #[prop_or_default]
+    pub universe: Universe,
+}
+
+#[function_component]
+pub fn MissingTiles(props: &Props) -> Html {
+    let Props {
+        rack,
+        board,
+        universe,
+    } = props.clone();
+    let missing = use_state_eq(|| None::<Result<Option<Vec<Missing>>, TileSetError>>);
+
+    {
+        let missing = missing.clone();
+        use_effect_with_deps(move |_| missing.set(None), (board, rack, universe));
+    }
+
+    let onclick = {
+        let missing = missing.clone();
+        Callback::from(move |_| missing.set(Some(missing_tiles(universe.pieces(), board, rack))))
+    };
+
+    let result = match &*missing {
+        None => html! {},
+        Some(Err(error)) => html! {
+            <div class="notification is-danger">{ error.to_string() }</div>
+        },
+        Some(Ok(None)) => html! {
+            <div class="notification">
+                { format!("The whole rack needs more than {MAX_MISSING} draws, where the search stops") }
+            </div>
+        },
+        Some(Ok(Some(missing))) if missing.is_empty() => html! {
+            <div class="notification is-success">{ "The whole rack can be played already" }</div>
+        },
+        Some(Ok(Some(missing))) => {
+            let rows = missing
+                .iter()
+                .map(|missing| {
+                    html! {
+                        <tr>
+                            <td><Tile tile={missing.tile} disabled=true /></td>
+                            <td>{ missing.needed }</td>
+                            <td>{ missing.unseen }</td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>();
+            html! {
+                <table class="table is-striped">
+                <thead>
+                    <tr>
+                        <th>{ "Tile" }</th>
+                        <th>{ "Needed" }</th>
+                        <th>{ "Unseen" }</th>
+                    </tr>
+                </thead>
+                <tbody> { rows } </tbody>
+                </table>
+            }
+        }
+    };
+
+    html! {
+        <div class="container">
+            <h2 class="title">{ "Draws to play the whole rack" }</h2>
+            <button class="button is-primary" {onclick}>{ "Find" }</button>
+            { result }
+        </div>
+    }
+}
@@ -1,16 +1,34 @@
 pub mod picker;
+pub mod board;
 pub mod tile;
 pub mod color_pick;
 pub mod pool;
 pub mod chosen;
 pub mod combinations;
+pub mod countdown;
 pub mod debug;
+pub mod draw_odds;
+pub mod missing;
+pub mod okey;
+pub mod opponents;
+pub mod solve;
+pub mod rearrangement;
+pub mod simulate;
 
 pub use tile::Tile;
 pub use color_pick::ColorPick;
 pub use picker::Picker;
+pub use board::BoardEditor;
 pub use chosen::TileLine;
 pub use pool::Pool;
 pub use combinations::Combinations;
+pub use countdown::Countdown;
 pub use debug::DebugInfo;
+pub use draw_odds::DrawOdds;
+pub use missing::MissingTiles;
+pub use okey::OkeyEvaluation;
+pub use opponents::Opponents;
+pub use rearrangement::Rearrangement;
+pub use simulate::Simulate;
+pub use solve::Solve;
 
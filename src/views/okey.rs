@@ -0,0 +1,92 @@
+use yew::prelude::*;
+
+use super::{Tile, TileLine};
+use crate::model::{self, Okey, OkeyHand, TileSet};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub rack: TileSet,
+    pub indicator: model::Tile,
+}
+
+#[function_component]
+pub fn OkeyEvaluation(props: &Props) -> Html {
+    let Props { rack, indicator } = props.clone();
+    let evaluated = use_state_eq(|| None::<Option<OkeyHand>>);
+
+    {
+        let evaluated = evaluated.clone();
+        use_effect_with_deps(move |_| evaluated.set(None), (rack, indicator));
+    }
+
+    let Some(okey) = Okey::new(indicator) else {
+        return html! {};
+    };
+    let onclick = {
+        let evaluated = evaluated.clone();
+        Callback::from(move |_| evaluated.set(Some(okey.evaluate(rack))))
+    };
+
+    let result = match &*evaluated {
+        None => html! {},
+        Some(None) => html! {
+            <div class="notification is-warning">
+                { "An okey hand holds 14 tiles, or 15 before the discard" }
+            </div>
+        },
+        Some(Some(hand)) => {
+            let summary = if hand.is_winning() {
+                html! { <div class="notification is-success">{ "Winning hand" }</div> }
+            } else {
+                html! {
+                    <div class="notification">
+                        { format!("{} tiles left out", hand.left.len()) }
+                    </div>
+                }
+            };
+            let discard = match hand.discard {
+                Some(tile) => html! {
+                    <div class="level">
+                        <div class="level-left">
+                            <div class="level-item">{ "Discard" }</div>
+                            <div class="level-item"><Tile {tile} disabled=true /></div>
+                        </div>
+                    </div>
+                },
+                None => html! {},
+            };
+            let melds = hand
+                .melds
+                .iter()
+                .map(|&tiles| html! { <TileLine {tiles} disabled=true /> })
+                .collect::<Html>();
+            let left = if hand.left.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <>
+                        <h3 class="subtitle">{ "Left out" }</h3>
+                        <TileLine tiles={hand.left} disabled=true />
+                    </>
+                }
+            };
+            html! {
+                <>
+                    { summary }
+                    { discard }
+                    <h3 class="subtitle">{ if hand.pairs { "Pairs" } else { "Melds" } }</h3>
+                    { melds }
+                    { left }
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="container">
+            <h2 class="title">{ "Okey hand" }</h2>
+            <button class="button is-primary" {onclick}>{ "Evaluate" }</button>
+            { result }
+        </div>
+    }
+}
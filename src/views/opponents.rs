@@ -0,0 +1,156 @@
+use yew::prelude::*;
+
+use super::{Picker, TileLine};
+use crate::model::{self, unseen, Event, Inference, TileSet, TileSetError};
+use crate::utils::rng::Rng;
+
+const SAMPLES: usize = 200;
+const SEED: u64 = 0x5EED;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub rack: TileSet,
+    #[prop_or_default]
+    pub board: TileSet,
+    #[prop_or(3)]
+    pub opponents: usize,
+    // the log is kept by the parent, the simulation samples racks from it too
+    #[prop_or_default]
+    pub log: Vec<Event>,
+    #[prop_or_default]
+    pub on_log: Callback<Vec<Event>>,
+}
+
+#[function_component]
+pub fn Opponents(props: &Props) -> Html {
+    let Props {
+        rack,
+        board,
+        opponents,
+        log,
+        on_log,
+    } = props.clone();
+    let meld = use_state_eq(TileSet::default);
+    let rejected = use_state_eq(|| None::<TileSetError>);
+
+    let push = {
+        let (log, on_log) = (log.clone(), on_log.clone());
+        let meld = meld.clone();
+        Callback::from(move |event: Event| {
+            let mut events = log.clone();
+            events.push(event);
+            on_log.emit(events);
+            meld.set(TileSet::default());
+        })
+    };
+    let on_pick = {
+        let (meld, rejected) = (meld.clone(), rejected.clone());
+        Callback::from(move |tile: model::Tile| match meld.try_add(tile) {
+            Ok(picked) => {
+                meld.set(picked);
+                rejected.set(None);
+            }
+            Err(error) => rejected.set(Some(error)),
+        })
+    };
+    let on_remove = {
+        let meld = meld.clone();
+        Callback::from(move |tile: model::Tile| {
+            if let Ok(rest) = meld.try_remove(tile) {
+                meld.set(rest);
+            }
+        })
+    };
+
+    // sampling is slow, so it only reruns when what it depends on changes
+    let inferred = use_memo(
+        |&(rack, board, opponents, ref log)| {
+            let inference = Inference::replay(unseen(rack, board)?, opponents, log.iter())?;
+            let chances: Vec<_> = (0..opponents)
+                .map(|player| inference.go_out_chance(player, SAMPLES, &mut Rng::seeded(SEED)))
+                .collect();
+            Ok::<_, TileSetError>((inference, chances))
+        },
+        (rack, board, opponents, log.clone()),
+    );
+    let (inference, chances) = match &*inferred {
+        Ok(inferred) => inferred,
+        Err(error) => {
+            let undo = {
+                let log = log.clone();
+                Callback::from(move |_| {
+                    let mut events = log.clone();
+                    events.pop();
+                    on_log.emit(events);
+                })
+            };
+            return html! {
+                <div class="container">
+                    <h1 class="title">{ "Opponents" }</h1>
+                    <div class="notification is-danger">
+                        { format!("The logged plays don't fit the tiles seen: {error}") }
+                    </div>
+                    <button class="button" onclick={undo}>{ "Undo last event" }</button>
+                </div>
+            };
+        }
+    };
+    let rows = inference
+        .opponents()
+        .iter()
+        .enumerate()
+        .map(|(player, opponent)| {
+            let chance = chances[player]
+                .map(|chance| format!("{:.1}%", chance * 100.0))
+                .unwrap_or_else(|| "inconsistent".to_string());
+            let draw = push.reform(move |_| Event::Draw { player });
+            let pass = push.reform(move |_| Event::Pass { player });
+            let played = *meld;
+            let play = push.reform(move |_| Event::Play {
+                player,
+                meld: played,
+            });
+            html! {
+                <tr>
+                    <td>{ format!("Opponent {}", player + 1) }</td>
+                    <td>{ opponent.rack_size }</td>
+                    <td>{ opponent.passes }</td>
+                    <td>{ chance }</td>
+                    <td>
+                        <div class="buttons">
+                            <button class="button" onclick={draw}>{ "Draw" }</button>
+                            <button class="button" onclick={pass}>{ "Pass" }</button>
+                            <button class="button" onclick={play} disabled={played.is_empty()}>{ "Play meld" }</button>
+                        </div>
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Html>();
+
+    let rejection = match *rejected {
+        Some(error) => html! { <p class="help is-danger">{ error.to_string() }</p> },
+        None => html! {},
+    };
+
+    html! {
+        <div class="container">
+            <h1 class="title">{ "Opponents" }</h1>
+            <table class="table is-striped">
+            <thead>
+                <tr>
+                    <th>{ "Player" }</th>
+                    <th>{ "Rack" }</th>
+                    <th>{ "Passes" }</th>
+                    <th>{ "Goes out next turn" }</th>
+                    <th></th>
+                </tr>
+            </thead>
+            <tbody> { rows } </tbody>
+            </table>
+            <Picker {on_pick} />
+            { rejection }
+            <TileLine tiles={*meld} {on_remove} />
+        </div>
+    }
+}
@@ -1,12 +1,22 @@
 use yew::prelude::*;
 
 use super::{ColorPick, Tile};
-use crate::model::{self, Color, Value};
+use crate::model::{self, Color, Special, Universe, Value};
 
 #[derive(Properties, PartialEq)]
 pub struct Props {
     #[prop_or_default]
     pub on_pick: Callback<model::Tile>,
+    // the Okey indicator, whose next tile in its colour is wild
+    #[prop_or_default]
+    pub indicator: Option<model::Tile>,
+    #[prop_or_default]
+    pub on_indicator: Callback<Option<model::Tile>>,
+    // only the universe's own special jokers are offered
+    #[prop_or_default]
+    pub universe: Universe,
+    #[prop_or_default]
+    pub on_universe: Callback<Universe>,
 }
 
 #[function_component]
@@ -16,6 +26,7 @@ pub fn Picker(props: &Props) -> Html {
         let chosen_color = chosen_color.clone();
         Callback::from(move |s: Color| chosen_color.set(s))
     };
+    let picking_indicator = use_state_eq(|| false);
 
     let colors = model::Color::all();
 
@@ -38,15 +49,27 @@ pub fn Picker(props: &Props) -> Html {
             color: *chosen_color.clone(),
             value,
         })
-        .chain([model::Tile::Joker]);
+        .chain([model::Tile::Joker])
+        .chain(
+            Special::all()
+                .filter(|special| props.universe.pieces().special_jokers().contains(special))
+                .map(model::Tile::Special),
+        );
 
     let values = tiles
         .map(|tile| {
             let on_click = {
                 let tile = tile.clone();
                 let on_pick = props.on_pick.clone();
+                let on_indicator = props.on_indicator.clone();
+                let picking_indicator = picking_indicator.clone();
                 Callback::from(move |_| {
-                    on_pick.emit(tile.clone());
+                    if *picking_indicator && matches!(tile, model::Tile::Normal { .. }) {
+                        picking_indicator.set(false);
+                        on_indicator.emit(Some(tile));
+                    } else {
+                        on_pick.emit(tile.clone());
+                    }
                 })
             };
             html! {
@@ -57,12 +80,72 @@ pub fn Picker(props: &Props) -> Html {
         })
         .collect::<Html>();
 
+    let okey = {
+        let indicator = props.indicator;
+        let picking = *picking_indicator;
+        let class = classes!(
+            "button",
+            (indicator.is_some() || picking).then_some("is-selected is-info")
+        );
+        let onclick = {
+            let on_indicator = props.on_indicator.clone();
+            let picking_indicator = picking_indicator.clone();
+            Callback::from(move |_| {
+                if indicator.is_some() {
+                    on_indicator.emit(None);
+                } else {
+                    picking_indicator.set(!picking);
+                }
+            })
+        };
+        let shown = match indicator.and_then(model::Okey::new) {
+            Some(okey) => html! {
+                <>
+                    <div class="level-item">{ "Indicator" }</div>
+                    <div class="level-item"><Tile tile={okey.indicator()} disabled=true /></div>
+                    <div class="level-item">{ "Okey" }</div>
+                    <div class="level-item"><Tile tile={okey.okey()} disabled=true /></div>
+                </>
+            },
+            None if picking => html! {
+                <div class="level-item">{ "Pick the indicator tile" }</div>
+            },
+            None => html! {},
+        };
+        html! {
+            <div class="level-left">
+                <div class="level-item">
+                    <button {class} {onclick}>{ "Okey" }</button>
+                </div>
+                { shown }
+            </div>
+        }
+    };
+
+    let universes = Universe::all()
+        .map(|option| {
+            let class = classes!(
+                "button",
+                (option == props.universe).then_some("is-selected is-info")
+            );
+            let onclick = props.on_universe.reform(move |_| option);
+            html! {
+                <button {class} {onclick}>{ option.pieces().name() }</button>
+            }
+        })
+        .collect::<Html>();
+    let universes = html! {
+        <div class="level-right">
+            <div class="level-item buttons has-addons">{ universes }</div>
+        </div>
+    };
+
     html! {
         <div class="container">
             <h1 class = "title"> {"Pick a tile"} </h1>
             <div class = "level"> { panels }  </div>
             <div class = "level"> { values }  </div>
+            <div class = "level"> { okey } { universes } </div>
         </div>
     }
 }
-
@@ -1,32 +1,152 @@
 use yew::prelude::*;
 
-use super::{Picker, TileLine, Combinations};
-use crate::model::{self, TileSet};
+use super::{
+    BoardEditor, Combinations, Countdown, DrawOdds, MissingTiles, OkeyEvaluation, Opponents,
+    Picker, Simulate, Solve, TileLine,
+};
+use crate::model::{self, Board, CacheStats, Event, TileSet, TileSetError, Universe};
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    #[prop_or_default]
+    pub on_cache: Callback<CacheStats>,
+}
 
 #[function_component]
-pub fn Pool() -> Html {
-    let pool = use_state_eq(|| TileSet::default());
+pub fn Pool(props: &Props) -> Html {
+    let rack = use_state_eq(TileSet::default);
+    let board = use_state_eq(Board::default);
+    // picked board tiles are drafted into a meld before it's laid
+    let draft = use_state_eq(TileSet::default);
+    let to_board = use_state_eq(|| false);
+    let indicator = use_state_eq(|| None::<model::Tile>);
+    let rejected = use_state_eq(|| None::<TileSetError>);
+    let universe = use_state_eq(Universe::default);
+    let log = use_state_eq(Vec::<Event>::new);
+
+    // a pick counts against every copy already on the rack, board or draft,
+    // and the universe's deck has one of each of its special jokers
     let on_pick = {
-        let pool = pool.clone();
+        let (rack, board, draft) = (rack.clone(), board.clone(), draft.clone());
+        let (to_board, rejected) = (to_board.clone(), rejected.clone());
+        let deck = universe.pieces().deck();
         Callback::from(move |tile: model::Tile| {
-            pool.set(pool.add(tile));
+            let target = if *to_board { &draft } else { &rack };
+            let picked = rack
+                .checked_add(board.tiles())
+                .and_then(|seen| seen.checked_add(*draft))
+                .and_then(|seen| seen.try_add(tile))
+                .and_then(|seen| {
+                    if seen <= deck {
+                        Ok(seen)
+                    } else {
+                        Err(TileSetError::TooManyCopies(tile))
+                    }
+                })
+                .and_then(|_| target.try_add(tile));
+            match picked {
+                Ok(picked) => {
+                    target.set(picked);
+                    rejected.set(None);
+                }
+                Err(error) => rejected.set(Some(error)),
+            }
         })
     };
-    let on_remove = {
-        let pool = pool.clone();
+    let remover = |tiles: &UseStateHandle<TileSet>| {
+        let tiles = tiles.clone();
         Callback::from(move |tile: model::Tile| {
-            pool.set(pool.remove(tile));
+            if let Ok(rest) = tiles.try_remove(tile) {
+                tiles.set(rest);
+            }
+        })
+    };
+    let on_board = {
+        let board = board.clone();
+        Callback::from(move |changed| board.set(changed))
+    };
+    let on_play = {
+        let (board, rack) = (board.clone(), rack.clone());
+        Callback::from(move |(after, left): (Board, TileSet)| {
+            board.set(after);
+            rack.set(left);
         })
     };
+    let on_indicator = {
+        let indicator = indicator.clone();
+        Callback::from(move |picked| indicator.set(picked))
+    };
+    let on_universe = {
+        let universe = universe.clone();
+        Callback::from(move |chosen| universe.set(chosen))
+    };
+    let okey = match *indicator {
+        Some(indicator) => html! {
+            <div class="container">
+                <OkeyEvaluation rack={*rack} {indicator} />
+            </div>
+        },
+        None => html! {},
+    };
+    let rejection = match *rejected {
+        Some(error) => html! { <p class="help is-danger">{ error.to_string() }</p> },
+        None => html! {},
+    };
+    let on_log = {
+        let log = log.clone();
+        Callback::from(move |events| log.set(events))
+    };
+    let on_draft = {
+        let draft = draft.clone();
+        Callback::from(move |changed| draft.set(changed))
+    };
+    let target = |board: bool, name: &'static str| {
+        let to_board = to_board.clone();
+        let class = classes!(
+            "button",
+            (*to_board == board).then_some("is-selected is-info")
+        );
+        let onclick = Callback::from(move |_| to_board.set(board));
+        html! {
+            <button {class} {onclick}>{ name }</button>
+        }
+    };
+
     html! {
         <div class="container">
-            <Picker {on_pick} />
+            <div class="buttons has-addons">
+                { target(false, "Rack") }
+                { target(true, "Board") }
+            </div>
+            <Picker {on_pick} indicator={*indicator} {on_indicator} universe={*universe} {on_universe} />
+            { rejection }
+            <div class="container">
+                <h1 class="title"> {"Rack"} </h1>
+                <TileLine tiles={*rack} on_remove={remover(&rack)} sortable=true />
+            </div>
+            <Countdown board={(*board).clone()} rack={*rack} on_time_up={on_play.clone()} />
+            <div class="container">
+                <h1 class="title"> {"Board"} </h1>
+                <BoardEditor board={(*board).clone()} draft={*draft} {on_board} {on_draft} />
+            </div>
+            { okey }
+            <div class="container">
+                <Solve board={(*board).clone()} rack={*rack} universe={*universe} on_cache={props.on_cache.clone()} {on_play} />
+            </div>
+            <div class="container">
+                <Combinations tiles={*rack} />
+            </div>
+            <div class="container">
+                <DrawOdds rack={*rack} board={(*board).clone()} />
+            </div>
+            <div class="container">
+                <MissingTiles rack={*rack} board={board.tiles()} universe={*universe} />
+            </div>
             <div class="container">
-                <h1 class="title"> {"Chosen Tiles"} </h1>
-                <TileLine tiles={*pool} {on_remove} />
+                <Simulate board={(*board).clone()} rack={*rack} log={(*log).clone()} />
             </div>
             <div class="container">
-                <Combinations tiles={*pool} />
+                <Opponents rack={*rack} board={board.tiles()} log={(*log).clone()} {on_log} />
             </div>
         </div>
     }
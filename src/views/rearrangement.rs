@@ -0,0 +1,29 @@
+use yew::prelude::*;
+
+use crate::model::{plan, TileSet};
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub before: Vec<TileSet>,
+    pub after: Vec<TileSet>,
+}
+
+#[function_component]
+pub fn Rearrangement(props: &Props) -> Html {
+    let Props { before, after } = props;
+    let steps = plan(before, after)
+        .into_iter()
+        .map(|step| {
+            html! {
+                <li>{ step.to_string() }</li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="content">
+            <h2 class="title">{ "How to get there" }</h2>
+            <ol>{ steps }</ol>
+        </div>
+    }
+}
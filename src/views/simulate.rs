@@ -0,0 +1,162 @@
+use gloo_worker::{HandlerId, Spawnable, Worker, WorkerScope};
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use super::TileLine;
+use crate::model::{rank_plays, Board, Evaluation, Event, TileSet, TileSetError};
+
+const OPPONENTS: usize = 3;
+const SAMPLES: usize = 50;
+const SEED: u64 = 0x5EED;
+// built by Trunk from src/bin/worker.rs
+const WORKER: &str = "worker.js";
+
+type Ranked = Result<Vec<Evaluation>, TileSetError>;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Request {
+    // answers are tagged so one for a position since changed can be dropped
+    id: u64,
+    board: Board,
+    rack: TileSet,
+    log: Vec<Event>,
+}
+
+// The playouts take seconds, so they run in a web worker to keep the page
+// responsive. Nothing runs them from the command line, the crate only builds
+// the app and this worker.
+pub struct Simulator;
+
+impl Worker for Simulator {
+    type Message = ();
+    type Input = Request;
+    type Output = (u64, Ranked);
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        Self
+    }
+
+    fn update(&mut self, _scope: &WorkerScope<Self>, _msg: ()) {}
+
+    fn received(&mut self, scope: &WorkerScope<Self>, request: Request, id: HandlerId) {
+        let Request {
+            id: request_id,
+            board,
+            rack,
+            log,
+        } = request;
+        let ranked = rank_plays(&board, rack, OPPONENTS, &log, SAMPLES, SEED);
+        scope.respond(id, (request_id, ranked));
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub board: Board,
+    pub rack: TileSet,
+    // what the opponents have done so far, which the sampled racks follow
+    #[prop_or_default]
+    pub log: Vec<Event>,
+}
+
+#[function_component]
+pub fn Simulate(props: &Props) -> Html {
+    let Props { board, rack, log } = props.clone();
+    let ranked = use_state_eq(|| None::<Ranked>);
+    let running = use_state_eq(|| false);
+    let latest = use_mut_ref(|| 0_u64);
+
+    let worker = {
+        let (ranked, running, latest) = (ranked.clone(), running.clone(), latest.clone());
+        use_memo(
+            move |_| {
+                Simulator::spawner()
+                    .callback(move |(id, result)| {
+                        if id == *latest.borrow() {
+                            ranked.set(Some(result));
+                            running.set(false);
+                        }
+                    })
+                    .spawn(WORKER)
+            },
+            (),
+        )
+    };
+
+    {
+        let (ranked, running, latest) = (ranked.clone(), running.clone(), latest.clone());
+        use_effect_with_deps(
+            move |_| {
+                *latest.borrow_mut() += 1;
+                ranked.set(None);
+                running.set(false);
+            },
+            (board.clone(), rack, log.clone()),
+        );
+    }
+
+    let onclick = {
+        let running = running.clone();
+        Callback::from(move |_| {
+            let id = {
+                let mut latest = latest.borrow_mut();
+                *latest += 1;
+                *latest
+            };
+            worker.send(Request {
+                id,
+                board: board.clone(),
+                rack,
+                log: log.clone(),
+            });
+            running.set(true);
+        })
+    };
+
+    let class = classes!("button", "is-primary", running.then_some("is-loading"));
+    let result = match &*ranked {
+        None => html! {},
+        Some(Err(error)) => html! {
+            <div class="notification is-danger">{ error.to_string() }</div>
+        },
+        Some(Ok(ranked)) => {
+            let rows = ranked
+                .iter()
+                .map(|evaluation| {
+                    let play = if evaluation.played.is_empty() {
+                        html! { { "Draw" } }
+                    } else {
+                        html! { <TileLine tiles={evaluation.played} disabled=true /> }
+                    };
+                    html! {
+                        <tr>
+                            <td>{ play }</td>
+                            <td>{ format!("{:.1}", evaluation.score) }</td>
+                            <td>{ format!("{:.0}%", evaluation.wins * 100.0) }</td>
+                        </tr>
+                    }
+                })
+                .collect::<Html>();
+            html! {
+                <table class="table is-striped">
+                <thead>
+                    <tr>
+                        <th>{ "Play" }</th>
+                        <th>{ "Expected score" }</th>
+                        <th>{ "Wins" }</th>
+                    </tr>
+                </thead>
+                <tbody> { rows } </tbody>
+                </table>
+            }
+        }
+    };
+
+    html! {
+        <div class="container">
+            <h2 class="title">{ "Simulated plays" }</h2>
+            <button {class} {onclick} disabled={*running}>{ "Simulate" }</button>
+            { result }
+        </div>
+    }
+}
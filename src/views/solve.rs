@@ -0,0 +1,280 @@
+use yew::prelude::*;
+
+use super::{Rearrangement, TileLine};
+use crate::model::{
+    self, explain, untouched, Backend, Board, CacheStats, Conflict, Constraints, Rules, Solution,
+    TileSet, Universe,
+};
+
+const JOKER_LIMITS: [(Option<u8>, &str); 3] = [
+    (None, "Any jokers"),
+    (Some(0), "No jokers"),
+    (Some(1), "One joker"),
+];
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Props {
+    pub board: Board,
+    pub rack: TileSet,
+    // searches the universe's melds, special jokers included
+    #[prop_or_default]
+    pub universe: Universe,
+    #[prop_or_default]
+    pub on_cache: Callback<CacheStats>,
+    // the board and rack after playing a solution
+    #[prop_or_default]
+    pub on_play: Callback<(Board, TileSet)>,
+}
+
+#[function_component]
+pub fn Solve(props: &Props) -> Html {
+    let Props {
+        board: melds,
+        rack,
+        universe,
+        on_cache,
+        on_play,
+    } = props.clone();
+    let board = melds.tiles();
+    let backend = use_state_eq(Backend::default);
+    let solver = use_memo(
+        |&(backend, universe)| backend.solver_for(universe),
+        (*backend, universe),
+    );
+    let solution = use_state_eq(|| None::<Option<Solution>>);
+    let conflict = use_state_eq(|| None::<Conflict>);
+    let constraints = use_state_eq(Constraints::default);
+    let rules = use_state_eq(Rules::default);
+    let gentle = use_state_eq(|| false);
+
+    {
+        let (solution, conflict) = (solution.clone(), conflict.clone());
+        use_effect_with_deps(
+            move |_| {
+                solution.set(None);
+                conflict.set(None);
+            },
+            (melds.clone(), rack),
+        );
+    }
+
+    let backends = Backend::all()
+        .map(|option| {
+            let class = classes!(
+                "button",
+                (option == *backend).then_some("is-selected is-info")
+            );
+            let onclick = {
+                let backend = backend.clone();
+                Callback::from(move |_| backend.set(option))
+            };
+            html! {
+                <button {class} {onclick}>{ option.name() }</button>
+            }
+        })
+        .collect::<Html>();
+
+    let unserved = match *solver {
+        Some(_) => html! {},
+        None => {
+            let unserved = format!(
+                "{} can't solve {}",
+                backend.name(),
+                universe.pieces().name()
+            );
+            html! { <p class="help is-danger">{ unserved }</p> }
+        }
+    };
+
+    let toggle_gentle = {
+        let gentle = gentle.clone();
+        Callback::from(move |_| gentle.set(!*gentle))
+    };
+    let class = classes!("button", gentle.then_some("is-selected is-info"));
+
+    let rule_switch = Rules::all()
+        .map(|option| {
+            let class = classes!(
+                "button",
+                (option == *rules).then_some("is-selected is-info")
+            );
+            let onclick = {
+                let rules = rules.clone();
+                Callback::from(move |_| rules.set(option))
+            };
+            html! {
+                <button {class} {onclick}>{ option.name() }</button>
+            }
+        })
+        .collect::<Html>();
+
+    let update = |change: fn(&mut Constraints, TileSet)| {
+        let constraints = constraints.clone();
+        Callback::from(move |tiles: TileSet| {
+            let mut changed = (*constraints).clone();
+            change(&mut changed, tiles);
+            constraints.set(changed);
+        })
+    };
+    let toggle_pinned = update(Constraints::toggle_pinned);
+    let toggle_extend_only = update(Constraints::toggle_extend_only);
+    // clicking a rack tile keeps one more copy of it, then none again
+    let toggle_kept = {
+        let constraints = constraints.clone();
+        Callback::from(move |tile: model::Tile| {
+            let kept = constraints.kept;
+            let kept = if kept.amount(tile) < rack.amount(tile) {
+                kept.saturating_add(tile)
+            } else {
+                kept.saturating_remove(tile).saturating_remove(tile)
+            };
+            constraints.set(Constraints {
+                kept,
+                ..(*constraints).clone()
+            })
+        })
+    };
+
+    let joker_limits = JOKER_LIMITS
+        .into_iter()
+        .map(|(limit, name)| {
+            let class = classes!(
+                "button",
+                (constraints.joker_limit == limit).then_some("is-selected is-info")
+            );
+            let onclick = {
+                let constraints = constraints.clone();
+                Callback::from(move |_| {
+                    constraints.set(Constraints {
+                        joker_limit: limit,
+                        ..(*constraints).clone()
+                    })
+                })
+            };
+            html! {
+                <button {class} {onclick}>{ name }</button>
+            }
+        })
+        .collect::<Html>();
+
+    let meld_row = |meld: TileSet| {
+        let toggle = |callback: &Callback<TileSet>, selected: bool, name: &str| {
+            let class = classes!(
+                "button",
+                "is-small",
+                selected.then_some("is-selected is-info")
+            );
+            let onclick = callback.reform(move |_| meld);
+            html! {
+                <button {class} {onclick}>{ name.to_string() }</button>
+            }
+        };
+        html! {
+            <div class="level">
+                <div class="level-left">
+                    <TileLine tiles={meld} disabled=true />
+                </div>
+                <div class="level-right buttons has-addons">
+                    { toggle(&toggle_pinned, constraints.pinned.contains(&meld), "Pin") }
+                    { toggle(&toggle_extend_only, constraints.extend_only.contains(&meld), "Extend only") }
+                </div>
+            </div>
+        }
+    };
+
+    let onclick = {
+        let (solution, conflict) = (solution.clone(), conflict.clone());
+        let (constraints, rules, gentle) = ((*constraints).clone(), *rules, *gentle);
+        let melds = melds.clone();
+        let solver = solver.clone();
+        Callback::from(move |_| {
+            let Some(solver) = &*solver else {
+                return;
+            };
+            let found = if gentle {
+                solver.solve_gently(&melds, rack, rules, &constraints)
+            } else {
+                solver.solve_turn(&melds, rack, rules, &constraints)
+            };
+            if let Some(cache) = found.as_ref().and_then(|found| found.cache) {
+                on_cache.emit(cache);
+            }
+            if found.is_none() {
+                conflict.set(explain(solver.as_ref(), board, rack));
+            }
+            solution.set(Some(found))
+        })
+    };
+
+    let unkept = match constraints.playable(rack) {
+        Ok(_) => html! {},
+        Err(error) => html! {
+            <p class="help is-danger">{ format!("Can't keep that: {error}") }</p>
+        },
+    };
+
+    let result = match &*solution {
+        None => html! {},
+        Some(None) => match *conflict {
+            Some(conflict) => html! {
+                <div class="notification is-danger">
+                    <p>{ "The board can't be arranged" }</p>
+                    <p>{ conflict.to_string() }</p>
+                    <TileLine tiles={board} highlighted={conflict.tiles()} disabled=true />
+                </div>
+            },
+            None => html! {
+                <div class="notification is-danger">{ "No arrangement meets the constraints" }</div>
+            },
+        },
+        Some(Some(solution)) => {
+            let rows = solution
+                .melds
+                .iter()
+                .map(|&meld| meld_row(meld))
+                .collect::<Html>();
+            let before = melds.meld_sets();
+            let kept = untouched(&before, &solution.melds);
+            let swaps = solution
+                .swaps
+                .iter()
+                .map(|swap| html! { <li>{ swap.to_string() }</li> })
+                .collect::<Html>();
+            let play = match solution.board(&melds) {
+                Ok(after) => {
+                    let played = (after, rack - solution.played);
+                    let onclick = on_play.reform(move |_| played.clone());
+                    html! { <button class="button is-success" {onclick}>{ "Play it" }</button> }
+                }
+                Err(error) => html! { <p class="help is-danger">{ error.to_string() }</p> },
+            };
+            html! {
+                <div>
+                    <h2 class="subtitle">{ format!("Plays {} tiles", solution.played_count()) }</h2>
+                    <TileLine tiles={solution.played} disabled=true />
+                    <ul>{ swaps }</ul>
+                    <p>{ format!("Leaves {kept} of {} board melds as they were", before.len()) }</p>
+                    <div class="box">{ rows }</div>
+                    <Rearrangement {before} after={solution.melds.clone()} />
+                    { play }
+                </div>
+            }
+        }
+    };
+
+    html! {
+        <div class="container">
+            <h1 class="title">{ "Solve" }</h1>
+            <div class="buttons has-addons">{ backends }</div>
+            { unserved }
+            <div class="buttons has-addons">{ rule_switch }</div>
+            <div class="buttons has-addons">{ joker_limits }</div>
+            <button {class} onclick={toggle_gentle}>{ "Least disruption" }</button>
+            <h2 class="subtitle">{ "Keep in hand" }</h2>
+            <TileLine tiles={rack} highlighted={constraints.kept} on_remove={toggle_kept} />
+            { unkept }
+            <button class="button is-primary" {onclick} disabled={solver.is_none()}>{ "Solve" }</button>
+            { result }
+        </div>
+    }
+}
@@ -9,6 +9,8 @@ pub struct Props {
     pub on_click: Callback<MouseEvent>,
     #[prop_or_default]
     pub disabled: bool,
+    #[prop_or_default]
+    pub highlighted: bool,
 }
 
 #[function_component]
@@ -17,23 +19,39 @@ pub fn Tile(props: &Props) -> Html {
         tile,
         on_click,
         disabled,
+        highlighted,
     } = props.clone();
+    let highlight = highlighted.then_some("is-danger");
 
     match tile {
         model::Tile::Normal { color, value } => {
             let color_name = color.name();
             html! {
-                <button onclick={on_click} class={classes!("button", format!("pick-{color_name}"))} {disabled}> {format!("{value}")} </button>
+                <button onclick={on_click} class={classes!("button", format!("pick-{color_name}"), highlight)} {disabled}> {format!("{value}")} </button>
             }
         }
         model::Tile::Joker => {
             html! {
-                <button onclick={on_click} class={classes!("button")}>
+                <button onclick={on_click} class={classes!("button", highlight)}>
                     <span class = "icon">
                         <i class="fa-solid fa-face-smile"></i>
                     </span>
                 </button>
             }
         }
+        model::Tile::Special(special) => {
+            let icon = match special {
+                model::Special::Mirror => "fa-right-left",
+                model::Special::ColorChange => "fa-palette",
+                model::Special::Duplicate => "fa-clone",
+            };
+            html! {
+                <button onclick={on_click} class={classes!("button", "pick-special", highlight)} title={special.name()} {disabled}>
+                    <span class = "icon">
+                        <i class={classes!("fa-solid", icon)}></i>
+                    </span>
+                </button>
+            }
+        }
     }
 }